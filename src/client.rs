@@ -0,0 +1,775 @@
+//! # Core Client
+//!
+//! Provides [`query`] for simple single-turn streaming requests, and [`Client`] for
+//! multi-turn conversations that keep their own history. Both talk to an OpenAI-compatible
+//! `/chat/completions` endpoint with `stream: true` and parse the resulting
+//! Server-Sent-Events into a stream of [`ContentBlock`]s.
+
+use crate::context;
+use crate::embeddings::{self, EmbeddingOptions};
+use crate::error::{Error, Result};
+use crate::hooks::{HookDecision, PostToolUseEvent, PreToolUseEvent, UserPromptSubmitEvent};
+use crate::images::{self, ImageGenerationOptions};
+use crate::polling;
+use crate::tools::Tool;
+use crate::types::{
+    content_to_openai, AgentOptions, ContentBlock, ImageBlock, Message, MessageRole, OpenAIContent,
+    TextBlock, ToolResultBlock, ToolUseBlock,
+};
+use crate::upload;
+use crate::utils::{parse_sse_data, StreamToolCallDelta, ToolCallAggregator};
+use base64::Engine as _;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OpenAIMessage {
+    pub role: String,
+    pub content: OpenAIContent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OpenAIToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OpenAIToolCall {
+    pub id: String,
+    pub r#type: String,
+    pub function: OpenAIFunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OpenAIFunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIToolDef<'a> {
+    r#type: &'static str,
+    function: OpenAIFunctionDef<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIFunctionDef<'a> {
+    name: &'a str,
+    description: &'a str,
+    parameters: &'a serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<OpenAIMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<OpenAIToolDef<'a>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<StreamToolCallDelta>>,
+}
+
+fn role_str(role: MessageRole) -> &'static str {
+    match role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+    }
+}
+
+/// Convert one history message into its OpenAI wire form.
+///
+/// A message carrying a [`ContentBlock::ToolResult`] serializes as a `role: "tool"` message
+/// with `tool_call_id` set, per the OpenAI function-calling protocol. A message carrying
+/// [`ContentBlock::ToolUse`] blocks (always assistant messages) serializes those as
+/// `tool_calls` instead of folding them into `content`.
+fn message_to_openai(message: &Message, options: &AgentOptions) -> OpenAIMessage {
+    if let Some(result) = message.content.iter().find_map(|b| match b {
+        ContentBlock::ToolResult(r) => Some(r),
+        _ => None,
+    }) {
+        return OpenAIMessage {
+            role: role_str(message.role).to_string(),
+            content: OpenAIContent::Text(result.content.clone()),
+            tool_calls: None,
+            tool_call_id: Some(result.tool_use_id.clone()),
+        };
+    }
+
+    let tool_calls: Vec<OpenAIToolCall> = message
+        .content
+        .iter()
+        .filter_map(|b| match b {
+            ContentBlock::ToolUse(call) => Some(OpenAIToolCall {
+                id: call.id.clone(),
+                r#type: "function".to_string(),
+                function: OpenAIFunctionCall {
+                    name: call.name.clone(),
+                    arguments: call.input.to_string(),
+                },
+            }),
+            _ => None,
+        })
+        .collect();
+
+    OpenAIMessage {
+        role: role_str(message.role).to_string(),
+        content: content_to_openai(&message.content, options.content_encoding()),
+        tool_calls: if tool_calls.is_empty() {
+            None
+        } else {
+            Some(tool_calls)
+        },
+        tool_call_id: None,
+    }
+}
+
+pub(crate) fn build_openai_messages(history: &[Message], options: &AgentOptions) -> Vec<OpenAIMessage> {
+    history
+        .iter()
+        .map(|m| message_to_openai(m, options))
+        .collect()
+}
+
+fn build_tool_defs(tools: &[Tool]) -> Option<Vec<OpenAIToolDef<'_>>> {
+    if tools.is_empty() {
+        return None;
+    }
+    Some(
+        tools
+            .iter()
+            .map(|t| OpenAIToolDef {
+                r#type: "function",
+                function: OpenAIFunctionDef {
+                    name: t.name(),
+                    description: t.description(),
+                    parameters: t.parameters(),
+                },
+            })
+            .collect(),
+    )
+}
+
+fn find_tool<'a>(tools: &'a [Tool], name: &str) -> Option<&'a Tool> {
+    tools.iter().find(|t| t.name() == name)
+}
+
+/// Run the registered `UserPromptSubmit` hooks over a prompt, returning the (possibly
+/// rewritten) prompt to actually send, or an error if a hook blocked it.
+fn apply_user_prompt_submit_hooks(
+    options: &AgentOptions,
+    prompt: String,
+    history: &[Message],
+) -> Result<String> {
+    let event = UserPromptSubmitEvent {
+        prompt,
+        history: history.to_vec(),
+    };
+
+    match options.hooks().run_user_prompt_submit(&event) {
+        HookDecision::Continue => Ok(event.prompt),
+        HookDecision::Block(reason) => {
+            Err(Error::invalid_input(format!("prompt blocked by hook: {reason}")))
+        }
+        HookDecision::Modify(value) => Ok(value
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or(event.prompt)),
+    }
+}
+
+fn build_http_client(options: &AgentOptions) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder.build().map_err(Error::from)
+}
+
+/// Send a chat completion request and return the resulting [`ContentStream`].
+///
+/// Most servers stream the response as Server-Sent Events, which is parsed directly.
+/// Job-based backends instead return a prediction handle (`{"status": ..., "urls":
+/// {"get": ...}}`); those are polled via [`polling::poll_until_complete`] on
+/// [`AgentOptions::poll_interval`] until a terminal status, and the job's `output` is
+/// wrapped in a single-item [`ContentStream`].
+async fn send_chat_request(
+    http: &reqwest::Client,
+    options: &AgentOptions,
+    history: &[Message],
+) -> Result<ContentStream> {
+    let body = ChatCompletionRequest {
+        model: options.model(),
+        messages: build_openai_messages(history, options),
+        stream: true,
+        temperature: options.temperature,
+        max_tokens: options.max_tokens,
+        tools: build_tool_defs(options.tools()),
+    };
+
+    let url = format!("{}/chat/completions", options.base_url().trim_end_matches('/'));
+    let response = http.post(url).json(&body).send().await?;
+
+    if !response.status().is_success() {
+        return Err(crate::error::error_from_response(response).await);
+    }
+
+    let is_event_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+
+    if !is_event_stream {
+        let body: serde_json::Value = response.json().await?;
+        let Some(get_url) = polling::job_urls(&body) else {
+            return Err(Error::stream(
+                "response was neither an SSE stream nor a recognized job handle",
+            ));
+        };
+        let output =
+            polling::poll_until_complete(http, &get_url, options.poll_interval(), options.poll_timeout()).await?;
+        let text = match output {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        return Ok(ContentStream::from_blocks(vec![ContentBlock::Text(TextBlock::new(text))]));
+    }
+
+    Ok(ContentStream::from_response(response))
+}
+
+// ============================================================================
+// CONTENT STREAM
+// ============================================================================
+
+/// A stream of [`ContentBlock`]s produced by an in-flight chat completion request.
+pub struct ContentStream {
+    inner: BoxStream<'static, Result<ContentBlock>>,
+}
+
+/// Internal state driving [`ContentStream`]'s `unfold`.
+struct StreamState {
+    bytes: BoxStream<'static, reqwest::Result<bytes::Bytes>>,
+    buffer: String,
+    tool_calls: ToolCallAggregator,
+    pending: std::collections::VecDeque<Result<ContentBlock>>,
+    done: bool,
+}
+
+/// Drive the state machine forward until it has an item to yield, or has truly finished.
+///
+/// Buffers complete SSE lines out of the byte stream, accumulates `delta.tool_calls`
+/// fragments as they arrive, and once the stream ends, flushes any aggregated tool calls
+/// as [`ContentBlock::ToolUse`] items before signalling completion.
+async fn advance_stream(mut state: StreamState) -> Option<(Result<ContentBlock>, StreamState)> {
+    loop {
+        if let Some(item) = state.pending.pop_front() {
+            return Some((item, state));
+        }
+        if state.done {
+            return None;
+        }
+
+        if let Some(idx) = state.buffer.find('\n') {
+            let line = state.buffer[..idx].trim_end_matches('\r').to_string();
+            state.buffer.drain(..=idx);
+
+            if let Some(data) = parse_sse_data(&line) {
+                match serde_json::from_str::<ChatCompletionChunk>(data) {
+                    Ok(chunk) => {
+                        if let Some(choice) = chunk.choices.into_iter().next() {
+                            if let Some(text) = choice.delta.content {
+                                if !text.is_empty() {
+                                    state
+                                        .pending
+                                        .push_back(Ok(ContentBlock::Text(TextBlock::new(text))));
+                                }
+                            }
+                            if let Some(tool_deltas) = choice.delta.tool_calls {
+                                state.tool_calls.ingest(tool_deltas);
+                            }
+                        }
+                    }
+                    Err(e) => state.pending.push_back(Err(Error::from(e))),
+                }
+            }
+            continue;
+        }
+
+        match state.bytes.next().await {
+            Some(Ok(bytes)) => state.buffer.push_str(&String::from_utf8_lossy(&bytes)),
+            Some(Err(e)) => {
+                state.pending.push_back(Err(Error::from(e)));
+                state.done = true;
+            }
+            None => {
+                state.done = true;
+                let tool_calls = std::mem::take(&mut state.tool_calls);
+                if !tool_calls.is_empty() {
+                    match tool_calls.finish() {
+                        Ok(calls) => {
+                            for call in calls {
+                                state.pending.push_back(Ok(ContentBlock::ToolUse(call)));
+                            }
+                        }
+                        Err(e) => state.pending.push_back(Err(e)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ContentStream {
+    fn from_response(response: reqwest::Response) -> Self {
+        let state = StreamState {
+            bytes: response.bytes_stream().boxed(),
+            buffer: String::new(),
+            tool_calls: ToolCallAggregator::default(),
+            pending: std::collections::VecDeque::new(),
+            done: false,
+        };
+
+        let inner = stream::unfold(Some(state), |state| async move {
+            advance_stream(state?).await.map(|(item, next)| (item, Some(next)))
+        })
+        .boxed();
+
+        Self { inner }
+    }
+
+    /// Wrap an already-collected list of content blocks as a stream, for the final turn
+    /// of the agentic tool-calling loop once a response has already been fully drained.
+    fn from_blocks(blocks: Vec<ContentBlock>) -> Self {
+        Self {
+            inner: stream::iter(blocks.into_iter().map(Ok)).boxed(),
+        }
+    }
+}
+
+impl futures::Stream for ContentStream {
+    type Item = Result<ContentBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+// ============================================================================
+// QUERY (SINGLE-TURN)
+// ============================================================================
+
+/// Send a single-turn query and return a stream of the response's content blocks.
+///
+/// Use this for one-off queries that don't need conversation state. For multi-turn
+/// conversations, use [`Client`] instead.
+pub async fn query(prompt: impl Into<String>, options: &AgentOptions) -> Result<ContentStream> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = options.system_prompt() {
+        messages.push(Message::system(system_prompt));
+    }
+    let prompt = apply_user_prompt_submit_hooks(options, prompt.into(), &messages)?;
+    messages.push(Message::user(prompt));
+
+    let http = build_http_client(options)?;
+    send_chat_request(&http, options, &messages).await
+}
+
+// ============================================================================
+// CLIENT (MULTI-TURN)
+// ============================================================================
+
+/// Stateful multi-turn conversation client with automatic history management.
+///
+/// Use this when you need to maintain conversation context across multiple turns.
+pub struct Client {
+    options: AgentOptions,
+    history: Vec<Message>,
+    http: reqwest::Client,
+    pending: Option<ContentStream>,
+}
+
+impl Client {
+    /// Create a new client from the given options, seeding history with the system
+    /// prompt if one was configured.
+    pub fn new(options: AgentOptions) -> Result<Self> {
+        let http = build_http_client(&options)?;
+        let mut history = Vec::new();
+        if let Some(system_prompt) = options.system_prompt() {
+            history.push(Message::system(system_prompt));
+        }
+
+        Ok(Self {
+            options,
+            history,
+            http,
+            pending: None,
+        })
+    }
+
+    /// The options this client was configured with.
+    pub fn options(&self) -> &AgentOptions {
+        &self.options
+    }
+
+    /// The conversation history accumulated so far.
+    pub fn history(&self) -> &Vec<Message> {
+        &self.history
+    }
+
+    /// Mutable access to the conversation history, for manual edits or truncation.
+    pub fn history_mut(&mut self) -> &mut Vec<Message> {
+        &mut self.history
+    }
+
+    /// Send a user turn, appending it to history and driving the agentic tool-calling
+    /// loop until the model returns a final, tool-call-free answer.
+    ///
+    /// Each step sends the current history to the model. If the response requests one or
+    /// more tool calls, each is dispatched to its registered handler (subject to
+    /// `PreToolUse`/`PostToolUse` hooks), the results are appended to history as
+    /// `role: "tool"` messages, and the model is re-invoked. This repeats until a response
+    /// contains no tool calls, or [`AgentOptions::max_steps`] round trips are exhausted,
+    /// at which point an error is returned. Call [`Client::receive`] in a loop afterward to
+    /// pull the final response's content blocks.
+    ///
+    /// If `auto_upload_threshold` and `upload_endpoint` are both configured, any inlined
+    /// base64 image already in
+    /// history whose payload exceeds it is uploaded via [`Client::upload_image`] and
+    /// replaced with the hosted URL before the request is built.
+    pub async fn send(&mut self, prompt: impl Into<String>) -> Result<()> {
+        let prompt = apply_user_prompt_submit_hooks(&self.options, prompt.into(), &self.history)?;
+        self.history.push(Message::user(prompt));
+        self.auto_upload_large_images().await?;
+
+        let mut step = 0usize;
+        loop {
+            if let Some(limit) = self.options.max_context_tokens() {
+                self.history = context::fit_to_budget(&self.history, limit, self.options.eviction_policy());
+            }
+
+            let mut stream = send_chat_request(&self.http, &self.options, &self.history).await?;
+
+            let mut final_blocks = Vec::new();
+            let mut tool_calls = Vec::new();
+            while let Some(block) = stream.next().await {
+                match block? {
+                    ContentBlock::ToolUse(call) => tool_calls.push(call),
+                    other => final_blocks.push(other),
+                }
+            }
+
+            if tool_calls.is_empty() {
+                self.pending = Some(ContentStream::from_blocks(final_blocks));
+                return Ok(());
+            }
+
+            step += 1;
+            if step > self.options.max_steps() {
+                return Err(Error::tool(format!(
+                    "exceeded max_steps ({}) without a final answer",
+                    self.options.max_steps()
+                )));
+            }
+
+            let mut assistant_content = final_blocks;
+            assistant_content.extend(tool_calls.iter().cloned().map(ContentBlock::ToolUse));
+            self.history.push(Message::assistant(assistant_content));
+
+            for call in tool_calls {
+                let result = self.execute_tool(&call).await;
+                let (content, is_error) = match result {
+                    Ok(output) => (output, None),
+                    Err(e) => (e.to_string(), Some(true)),
+                };
+                self.history.push(Message::new(
+                    MessageRole::Tool,
+                    vec![ContentBlock::ToolResult(ToolResultBlock {
+                        tool_use_id: call.id,
+                        content,
+                        is_error,
+                    })],
+                ));
+            }
+        }
+    }
+
+    /// Run `PreToolUse`/`PostToolUse` hooks around dispatching one tool call to its
+    /// registered handler. A `PreToolUse` hook returning [`HookDecision::Modify`] replaces
+    /// the input the handler is called with.
+    async fn execute_tool(&self, call: &ToolUseBlock) -> Result<String> {
+        let pre_event = PreToolUseEvent {
+            tool_name: call.name.clone(),
+            input: call.input.clone(),
+            id: call.id.clone(),
+            history: self.history.clone(),
+        };
+        let input = match self.options.hooks().run_pre_tool_use(&pre_event) {
+            HookDecision::Continue => call.input.clone(),
+            HookDecision::Block(reason) => {
+                return Err(Error::tool(format!(
+                    "tool '{}' blocked by hook: {reason}",
+                    call.name
+                )));
+            }
+            HookDecision::Modify(value) => value,
+        };
+
+        let tool = find_tool(self.options.tools(), &call.name)
+            .ok_or_else(|| Error::tool(format!("tool '{}' is not registered", call.name)))?;
+        let result = tool.call(input.clone()).await?;
+
+        let post_event = PostToolUseEvent {
+            tool_name: call.name.clone(),
+            input,
+            id: call.id.clone(),
+            result: result.clone(),
+            history: self.history.clone(),
+        };
+        self.options.hooks().run_post_tool_use(&post_event);
+
+        Ok(result)
+    }
+
+    /// Receive the next content block of the in-flight response, or `None` once it's
+    /// finished.
+    pub async fn receive(&mut self) -> Result<Option<ContentBlock>> {
+        let Some(stream) = self.pending.as_mut() else {
+            return Ok(None);
+        };
+
+        match stream.next().await {
+            Some(Ok(block)) => Ok(Some(block)),
+            Some(Err(e)) => Err(e),
+            None => {
+                self.pending = None;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Generate images from a text prompt via an OpenAI-compatible
+    /// `/v1/images/generations` endpoint, using this client's configured model.
+    ///
+    /// Transparently awaits job-based backends: if the response is a prediction handle
+    /// (`{"status": ..., "urls": {"get": ...}}`) rather than the image data directly, it's
+    /// polled via [`AgentOptions::poll_interval`] until the job succeeds or fails.
+    pub async fn generate_images(&self, options: &ImageGenerationOptions) -> Result<Vec<ImageBlock>> {
+        let body = images::build_request(self.options.model(), options);
+        let url = format!(
+            "{}/images/generations",
+            self.options.base_url().trim_end_matches('/')
+        );
+
+        let response = self.http.post(url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::error::error_from_response(response).await);
+        }
+
+        let value: serde_json::Value = response.json().await?;
+        let resolved = match polling::job_urls(&value) {
+            Some(get_url) => {
+                polling::poll_until_complete(&self.http, &get_url, self.options.poll_interval(), self.options.poll_timeout())
+                    .await?
+            }
+            None => value,
+        };
+
+        let parsed = serde_json::from_value(resolved)?;
+        images::parse_response(parsed)
+    }
+
+    /// Generate embeddings for one or more input strings via an OpenAI-compatible
+    /// `/v1/embeddings` endpoint, using this client's configured model.
+    ///
+    /// Inputs exceeding the model's token limit are split into multiple chunks before
+    /// sending (see [`crate::embeddings`]), so the returned vector may contain more
+    /// entries than `options` had inputs.
+    pub async fn embed(&self, options: &EmbeddingOptions) -> Result<Vec<Vec<f32>>> {
+        let inputs = embeddings::prepare_inputs(self.options.model(), &options.input);
+        let body = embeddings::build_request(self.options.model(), &inputs, options.dimensions);
+
+        let url = format!(
+            "{}/embeddings",
+            self.options.base_url().trim_end_matches('/')
+        );
+
+        let response = self.http.post(url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::error::error_from_response(response).await);
+        }
+
+        let parsed = response.json().await?;
+        Ok(embeddings::parse_response(parsed))
+    }
+
+    /// Upload raw image bytes to the configured media endpoint as `multipart/form-data`,
+    /// returning an [`ImageBlock`] that points at the hosted URL rather than inlining the
+    /// bytes as a `data:` URI. Requires [`AgentOptions::upload_endpoint`] to be set.
+    pub async fn upload_image(&self, bytes: impl Into<Vec<u8>>, mime: impl AsRef<str>) -> Result<ImageBlock> {
+        let endpoint = self.options.upload_endpoint().ok_or_else(|| {
+            Error::config("upload_endpoint must be set on AgentOptions to use Client::upload_image")
+        })?;
+
+        let form = upload::build_form(bytes.into(), mime.as_ref())?;
+        let response = self.http.post(endpoint).multipart(form).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::error::error_from_response(response).await);
+        }
+
+        let parsed = response.json().await?;
+        ImageBlock::from_url(upload::parse_response(parsed))
+    }
+
+    /// Read an image file from disk and upload it via [`Client::upload_image`].
+    pub async fn upload_image_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        mime: impl AsRef<str>,
+    ) -> Result<ImageBlock> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| Error::other(format!("failed to read image file {}: {e}", path.display())))?;
+        self.upload_image(bytes, mime).await
+    }
+
+    /// Replace any inlined base64 [`ImageBlock`] in history whose payload exceeds
+    /// `auto_upload_threshold` with a hosted URL uploaded to `upload_endpoint`. A no-op
+    /// unless both options are configured.
+    async fn auto_upload_large_images(&mut self) -> Result<()> {
+        if self.options.upload_endpoint().is_none() {
+            return Ok(());
+        }
+        let Some(threshold) = self.options.auto_upload_threshold() else {
+            return Ok(());
+        };
+
+        let mut to_upload = Vec::new();
+        for (msg_idx, message) in self.history.iter().enumerate() {
+            for (block_idx, block) in message.content.iter().enumerate() {
+                if let ContentBlock::Image(img) = block {
+                    if let Some((mime, data)) = img.inline_data() {
+                        if data.len() > threshold {
+                            to_upload.push((msg_idx, block_idx, mime.to_string(), data.to_string(), img.detail()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (msg_idx, block_idx, mime, data, detail) in to_upload {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&data)
+                .map_err(|e| Error::invalid_input(format!("invalid base64 image data: {e}")))?;
+            let uploaded = self.upload_image(bytes, &mime).await?.with_detail(detail);
+            self.history[msg_idx].content[block_idx] = ContentBlock::Image(uploaded);
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolUseBlock;
+
+    fn test_options() -> AgentOptions {
+        AgentOptions::builder()
+            .model("test-model")
+            .base_url("http://localhost:1234/v1")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tool_use_message_serializes_as_tool_calls() {
+        let message = Message::assistant(vec![ContentBlock::ToolUse(ToolUseBlock {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"city": "paris"}),
+        })]);
+
+        let wire = message_to_openai(&message, &test_options());
+
+        assert_eq!(wire.role, "assistant");
+        assert_eq!(wire.tool_call_id, None);
+        let tool_calls = wire.tool_calls.expect("expected tool_calls to be set");
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, "call_1");
+        assert_eq!(tool_calls[0].function.name, "get_weather");
+        assert_eq!(tool_calls[0].function.arguments, "{\"city\":\"paris\"}");
+    }
+
+    #[test]
+    fn test_tool_result_message_serializes_as_tool_role() {
+        let message = Message::new(
+            MessageRole::Tool,
+            vec![ContentBlock::ToolResult(ToolResultBlock {
+                tool_use_id: "call_1".to_string(),
+                content: "15 degrees and sunny".to_string(),
+                is_error: None,
+            })],
+        );
+
+        let wire = message_to_openai(&message, &test_options());
+
+        assert_eq!(wire.role, "tool");
+        assert_eq!(wire.tool_call_id, Some("call_1".to_string()));
+        assert!(wire.tool_calls.is_none());
+        match wire.content {
+            OpenAIContent::Text(text) => assert_eq!(text, "15 degrees and sunny"),
+            OpenAIContent::Parts(_) => panic!("tool result content should be a plain string"),
+        }
+    }
+
+    #[test]
+    fn test_build_tool_defs_empty_when_no_tools_registered() {
+        assert!(build_tool_defs(&[]).is_none());
+    }
+
+    #[test]
+    fn test_find_tool_matches_by_name() {
+        let tool = crate::tools::tool("ping", "pings something")
+            .handler(|_input| async move { Ok("pong".to_string()) });
+        let tools = vec![tool];
+
+        assert!(find_tool(&tools, "ping").is_some());
+        assert!(find_tool(&tools, "missing").is_none());
+    }
+}
@@ -0,0 +1,78 @@
+//! # Provider Configuration Helpers
+//!
+//! Small helpers for resolving the base URL and model name of the common local
+//! OpenAI-compatible servers, with environment variable overrides so the same code can
+//! run against whichever server happens to be running without recompiling.
+
+use std::env;
+
+/// Supported local LLM providers.
+///
+/// Each variant knows its own conventional default base URL, which [`get_base_url`] falls
+/// back to when no environment override is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// LM Studio, listening on `http://localhost:1234/v1` by default.
+    LMStudio,
+    /// Ollama, listening on `http://localhost:11434/v1` by default.
+    Ollama,
+    /// llama.cpp's server, listening on `http://localhost:8080/v1` by default.
+    LlamaCpp,
+    /// vLLM, listening on `http://localhost:8000/v1` by default.
+    VLLM,
+}
+
+impl Provider {
+    fn default_base_url(self) -> &'static str {
+        match self {
+            Provider::LMStudio => "http://localhost:1234/v1",
+            Provider::Ollama => "http://localhost:11434/v1",
+            Provider::LlamaCpp => "http://localhost:8080/v1",
+            Provider::VLLM => "http://localhost:8000/v1",
+        }
+    }
+
+    fn env_var(self) -> &'static str {
+        match self {
+            Provider::LMStudio => "LMSTUDIO_BASE_URL",
+            Provider::Ollama => "OLLAMA_BASE_URL",
+            Provider::LlamaCpp => "LLAMACPP_BASE_URL",
+            Provider::VLLM => "VLLM_BASE_URL",
+        }
+    }
+
+    fn model_env_var(self) -> &'static str {
+        match self {
+            Provider::LMStudio => "LMSTUDIO_MODEL",
+            Provider::Ollama => "OLLAMA_MODEL",
+            Provider::LlamaCpp => "LLAMACPP_MODEL",
+            Provider::VLLM => "VLLM_MODEL",
+        }
+    }
+}
+
+/// Get the base URL for API requests, with support for environment variable overrides.
+///
+/// Priority: environment variable > provider default.
+pub fn get_base_url(provider: Provider) -> String {
+    if let Ok(url) = env::var(provider.env_var()) {
+        if !url.is_empty() {
+            return url;
+        }
+    }
+    provider.default_base_url().to_string()
+}
+
+/// Get the model name for requests, with optional environment variable override.
+///
+/// Priority: environment variable (if `prefer_env` is true) > `fallback`.
+pub fn get_model(provider: Provider, fallback: &str, prefer_env: bool) -> String {
+    if prefer_env {
+        if let Ok(model) = env::var(provider.model_env_var()) {
+            if !model.is_empty() {
+                return model;
+            }
+        }
+    }
+    fallback.to_string()
+}
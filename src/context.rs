@@ -0,0 +1,326 @@
+//! # Context Window Management
+//!
+//! Manual utilities for estimating how many tokens a conversation history will occupy
+//! and for trimming that history back down when it's getting close to the model's
+//! context window, so callers can avoid context-length errors proactively.
+//!
+//! [`estimate_tokens`] is a cheap character-based approximation suitable for a quick
+//! check; [`estimate_tokens_precise`] instead runs text through the bundled BPE tokenizer
+//! and prices images via a fixed per-[`crate::ImageDetail`] cost table, and is what
+//! [`crate::Client::send`] uses to enforce `max_context_tokens`.
+
+use crate::tokenizer;
+use crate::types::{ContentBlock, ImageDetail, Message, MessageRole};
+
+/// Approximate characters-per-token used by the character-based estimator.
+///
+/// This is a rough approximation (70-85% accurate across model families); it avoids
+/// depending on a specific tokenizer just to decide whether history needs trimming.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Flat token cost for a `Low`-detail image, matching OpenAI's fixed low-detail budget.
+const IMAGE_TOKENS_LOW: usize = 85;
+
+/// Worst-case token cost for a `High`-detail (or `Auto`, treated conservatively) image.
+///
+/// Real vision APIs price high-detail images by tiling the decoded image and charging
+/// per tile; since `ImageBlock` never decodes pixel dimensions, this uses a fixed
+/// worst-case estimate (a large image split into the maximum number of tiles) rather
+/// than a size-dependent calculation.
+const IMAGE_TOKENS_HIGH: usize = 765;
+
+/// Estimate the number of tokens in a message history using a character-based approximation.
+pub fn estimate_tokens(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .map(|m| {
+            m.content
+                .iter()
+                .map(|block| match block {
+                    ContentBlock::Text(t) => t.text.len(),
+                    ContentBlock::Image(_) => 0,
+                    ContentBlock::ToolUse(tu) => tu.name.len() + tu.input.to_string().len(),
+                    ContentBlock::ToolResult(tr) => tr.content.len(),
+                })
+                .sum::<usize>()
+        })
+        .sum::<usize>()
+        .div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Fixed token cost of an image at a given detail level (see [`IMAGE_TOKENS_LOW`] and
+/// [`IMAGE_TOKENS_HIGH`]).
+fn image_token_cost(detail: ImageDetail) -> usize {
+    match detail {
+        ImageDetail::Low => IMAGE_TOKENS_LOW,
+        ImageDetail::High | ImageDetail::Auto => IMAGE_TOKENS_HIGH,
+    }
+}
+
+/// Estimate the number of tokens in a message history using the bundled BPE tokenizer
+/// for text and a fixed per-detail cost table for images.
+///
+/// More accurate than [`estimate_tokens`]'s character heuristic, at the cost of running
+/// the actual BPE merge loop over every text block. This is what [`crate::Client::send`]
+/// uses to decide whether `max_context_tokens` requires trimming history.
+pub fn estimate_tokens_precise(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .map(|m| {
+            m.content
+                .iter()
+                .map(|block| match block {
+                    ContentBlock::Text(t) => tokenizer::count_tokens(&t.text),
+                    ContentBlock::Image(img) => image_token_cost(img.detail()),
+                    ContentBlock::ToolUse(tu) => {
+                        tokenizer::count_tokens(&tu.name) + tokenizer::count_tokens(&tu.input.to_string())
+                    }
+                    ContentBlock::ToolResult(tr) => tokenizer::count_tokens(&tr.content),
+                })
+                .sum::<usize>()
+        })
+        .sum()
+}
+
+/// How conversation history is trimmed down when it no longer fits under
+/// `max_context_tokens`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Evict the oldest message, system messages included, repeatedly until the history
+    /// fits the token budget (or nothing is left to evict). A tool call and its tool
+    /// results are evicted together as one unit.
+    DropOldest,
+    /// Like `DropOldest`, but the system message is never evicted, even if everything
+    /// else has already been dropped and the history still doesn't fit.
+    #[default]
+    KeepSystem,
+    /// Ignore the token budget and simply keep only the most recent `n` non-system
+    /// turns, always preserving the system message if one is present. A turn is one
+    /// message, except that a tool call and its tool results count as one turn (see
+    /// [`truncate_messages`]).
+    KeepLastN(usize),
+}
+
+/// Trim `messages` down to fit within `limit` estimated tokens (per
+/// [`estimate_tokens_precise`]), using `policy` to decide what gets evicted.
+pub(crate) fn fit_to_budget(messages: &[Message], limit: usize, policy: EvictionPolicy) -> Vec<Message> {
+    match policy {
+        EvictionPolicy::KeepLastN(n) => truncate_messages(messages, n, true),
+        EvictionPolicy::DropOldest => evict_until_fits(messages, limit, false),
+        EvictionPolicy::KeepSystem => evict_until_fits(messages, limit, true),
+    }
+}
+
+/// Split `messages` into atomic eviction units: a run of one message, except an
+/// assistant message carrying `ToolUse` blocks, which is grouped together with the
+/// `role: "tool"` `ToolResult` messages immediately following it.
+///
+/// An OpenAI-compatible server rejects a request where a `tool_calls` message and its
+/// matching tool results have been split up (a dangling `tool_call_id` on either side),
+/// so eviction and truncation must always keep or drop a pair like this as one unit.
+fn group_units(messages: &[Message]) -> Vec<&[Message]> {
+    let mut units = Vec::new();
+    let mut start = 0;
+    while start < messages.len() {
+        let is_tool_call = messages[start]
+            .content
+            .iter()
+            .any(|b| matches!(b, ContentBlock::ToolUse(_)));
+
+        let mut end = start + 1;
+        if is_tool_call {
+            while end < messages.len() && messages[end].role == MessageRole::Tool {
+                end += 1;
+            }
+        }
+        units.push(&messages[start..end]);
+        start = end;
+    }
+    units
+}
+
+/// Repeatedly remove the oldest evictable unit (see [`group_units`]) until the history
+/// fits `limit` estimated tokens or there's nothing left that's allowed to be evicted.
+fn evict_until_fits(messages: &[Message], limit: usize, preserve_system: bool) -> Vec<Message> {
+    let mut trimmed = messages.to_vec();
+    while estimate_tokens_precise(&trimmed) > limit {
+        let units = group_units(&trimmed);
+        let evict_at = units
+            .iter()
+            .position(|unit| !preserve_system || unit[0].role != MessageRole::System);
+        match evict_at {
+            Some(idx) => {
+                let start: usize = units[..idx].iter().map(|u| u.len()).sum();
+                let end = start + units[idx].len();
+                trimmed.drain(start..end);
+            }
+            None => break,
+        }
+    }
+    trimmed
+}
+
+/// Check if a message history is approaching a token limit.
+///
+/// Returns `true` if the estimated token count exceeds `limit`. Useful for proactive
+/// truncation before sending a request that would otherwise be rejected.
+pub fn is_approaching_limit(messages: &[Message], limit: usize) -> bool {
+    estimate_tokens(messages) > limit
+}
+
+/// Truncate message history to keep only the most recent `keep_last` turns (see
+/// [`group_units`] for what counts as one turn — a `ToolUse`/`ToolResult` pair is never
+/// split across the cutoff).
+///
+/// Can optionally preserve the system message regardless of turn count.
+pub fn truncate_messages(messages: &[Message], keep_last: usize, preserve_system: bool) -> Vec<Message> {
+    let system: Option<&Message> = if preserve_system {
+        messages.iter().find(|m| m.role == MessageRole::System)
+    } else {
+        None
+    };
+
+    let non_system: Vec<Message> = messages
+        .iter()
+        .filter(|m| !preserve_system || m.role != MessageRole::System)
+        .cloned()
+        .collect();
+
+    let units = group_units(&non_system);
+    let keep_from_unit = units.len().saturating_sub(keep_last);
+    let rest: Vec<Message> = units[keep_from_unit..].iter().copied().flatten().cloned().collect();
+
+    match system {
+        Some(system) => std::iter::once(system.clone()).chain(rest).collect(),
+        None => rest,
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ImageBlock, TextBlock};
+
+    fn text_message(role: MessageRole, text: &str) -> Message {
+        Message::new(role, vec![ContentBlock::Text(TextBlock::new(text))])
+    }
+
+    fn image_message(detail: ImageDetail) -> Message {
+        let image = ImageBlock::from_url("http://example.com/a.png").unwrap().with_detail(detail);
+        Message::new(MessageRole::User, vec![ContentBlock::Image(image)])
+    }
+
+    #[test]
+    fn test_estimate_tokens_precise_counts_text() {
+        let messages = vec![text_message(MessageRole::User, "hello world")];
+        assert!(estimate_tokens_precise(&messages) > 0);
+    }
+
+    #[test]
+    fn test_estimate_tokens_precise_prices_images_by_detail() {
+        let low = vec![image_message(ImageDetail::Low)];
+        let high = vec![image_message(ImageDetail::High)];
+        assert_eq!(estimate_tokens_precise(&low), IMAGE_TOKENS_LOW);
+        assert_eq!(estimate_tokens_precise(&high), IMAGE_TOKENS_HIGH);
+    }
+
+    #[test]
+    fn test_fit_to_budget_keep_system_never_evicts_system_message() {
+        let messages = vec![
+            text_message(MessageRole::System, "be nice"),
+            text_message(MessageRole::User, &"padding ".repeat(200)),
+        ];
+        let trimmed = fit_to_budget(&messages, 1, EvictionPolicy::KeepSystem);
+        assert_eq!(trimmed.len(), 1);
+        assert_eq!(trimmed[0].role, MessageRole::System);
+    }
+
+    #[test]
+    fn test_fit_to_budget_drop_oldest_can_evict_system_message() {
+        let messages = vec![
+            text_message(MessageRole::System, "be nice"),
+            text_message(MessageRole::User, &"padding ".repeat(200)),
+        ];
+        let trimmed = fit_to_budget(&messages, 1, EvictionPolicy::DropOldest);
+        assert!(trimmed.iter().all(|m| m.role != MessageRole::System) || trimmed.is_empty());
+    }
+
+    #[test]
+    fn test_fit_to_budget_keep_last_n_preserves_system_and_recent_messages() {
+        let messages = vec![
+            text_message(MessageRole::System, "be nice"),
+            text_message(MessageRole::User, "first"),
+            text_message(MessageRole::User, "second"),
+        ];
+        let trimmed = fit_to_budget(&messages, 0, EvictionPolicy::KeepLastN(1));
+        assert_eq!(trimmed.len(), 2);
+        assert_eq!(trimmed[0].role, MessageRole::System);
+        assert_eq!(trimmed[1].role, MessageRole::User);
+    }
+
+    fn tool_call_message(id: &str) -> Message {
+        let call = crate::types::ToolUseBlock {
+            id: id.to_string(),
+            name: "lookup".to_string(),
+            input: serde_json::json!({}),
+        };
+        Message::assistant(vec![ContentBlock::ToolUse(call)])
+    }
+
+    fn tool_result_message(id: &str) -> Message {
+        let result = crate::types::ToolResultBlock {
+            tool_use_id: id.to_string(),
+            content: "result".to_string(),
+            is_error: None,
+        };
+        Message::new(MessageRole::Tool, vec![ContentBlock::ToolResult(result)])
+    }
+
+    #[test]
+    fn test_truncate_messages_keeps_tool_call_and_result_together() {
+        let messages = vec![
+            text_message(MessageRole::User, "first"),
+            tool_call_message("call_1"),
+            tool_result_message("call_1"),
+            text_message(MessageRole::User, "second"),
+        ];
+        let trimmed = truncate_messages(&messages, 1, false);
+        assert_eq!(trimmed.len(), 1);
+        assert_eq!(trimmed[0].content, messages[3].content);
+
+        let trimmed = truncate_messages(&messages, 2, false);
+        assert_eq!(trimmed.len(), 3);
+        assert!(trimmed.iter().any(|m| matches!(
+            m.content.first(),
+            Some(ContentBlock::ToolUse(_))
+        )));
+        assert!(trimmed.iter().any(|m| matches!(
+            m.content.first(),
+            Some(ContentBlock::ToolResult(_))
+        )));
+    }
+
+    #[test]
+    fn test_evict_until_fits_keeps_tool_call_and_result_together() {
+        let messages = vec![
+            text_message(MessageRole::System, "be nice"),
+            tool_call_message("call_1"),
+            tool_result_message("call_1"),
+            text_message(MessageRole::User, &"padding ".repeat(200)),
+        ];
+        let limit = estimate_tokens_precise(&messages[..3]);
+        let trimmed = fit_to_budget(&messages, limit, EvictionPolicy::KeepSystem);
+
+        let has_call = trimmed
+            .iter()
+            .any(|m| matches!(m.content.first(), Some(ContentBlock::ToolUse(_))));
+        let has_result = trimmed
+            .iter()
+            .any(|m| matches!(m.content.first(), Some(ContentBlock::ToolResult(_))));
+        assert_eq!(has_call, has_result, "tool call and result must be evicted together");
+    }
+}
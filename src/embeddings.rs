@@ -0,0 +1,176 @@
+//! # Embeddings
+//!
+//! [`crate::Client::embed`] wraps an OpenAI-compatible `/v1/embeddings` endpoint. Before
+//! sending, each input is checked against the model's token limit (see
+//! [`crate::max_tokens`]); inputs that fit are sent as-is, inputs that don't are split on
+//! whitespace boundaries into multiple chunks that each fit, so callers don't have to
+//! pre-budget every input themselves. A single word that alone exceeds the limit is not
+//! split further: it is sent as its own chunk, over the limit. The returned vector has one embedding per chunk
+//! actually sent, in order, which may be more than the number of inputs `options` was
+//! built with if any input needed splitting.
+
+use crate::error::Result;
+use crate::tokenizer;
+use serde::{Deserialize, Serialize};
+
+/// Options for a single [`crate::Client::embed`] call.
+///
+/// Built using [`EmbeddingOptions::builder`].
+#[derive(Debug, Clone)]
+pub struct EmbeddingOptions {
+    pub(crate) input: Vec<String>,
+    pub(crate) dimensions: Option<u32>,
+}
+
+impl EmbeddingOptions {
+    /// Start building options for an embeddings request with one input string.
+    pub fn builder(input: impl Into<String>) -> EmbeddingOptionsBuilder {
+        EmbeddingOptionsBuilder::new(input)
+    }
+}
+
+/// Builder for [`EmbeddingOptions`].
+#[derive(Debug, Clone)]
+pub struct EmbeddingOptionsBuilder {
+    input: Vec<String>,
+    dimensions: Option<u32>,
+}
+
+impl EmbeddingOptionsBuilder {
+    fn new(input: impl Into<String>) -> Self {
+        Self {
+            input: vec![input.into()],
+            dimensions: None,
+        }
+    }
+
+    /// Add another input string to embed in the same request.
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.input.push(input.into());
+        self
+    }
+
+    /// Truncate returned embeddings to this many dimensions. Only honored by models that
+    /// support it, such as the `text-embedding-3-*` family.
+    pub fn dimensions(mut self, dimensions: u32) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    /// Finish building the final [`EmbeddingOptions`].
+    pub fn build(self) -> Result<EmbeddingOptions> {
+        Ok(EmbeddingOptions {
+            input: self.input,
+            dimensions: self.dimensions,
+        })
+    }
+}
+
+/// Split `text` into chunks that each fit within `limit` tokens, breaking on whitespace so
+/// split points don't land mid-word. Returns `text` unsplit as the only chunk if it
+/// already fits. A single word longer than `limit` tokens becomes a chunk of its own and
+/// does not fit.
+fn split_to_fit(text: &str, limit: usize) -> Vec<String> {
+    if tokenizer::count_tokens(text) <= limit {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if !current.is_empty() && tokenizer::count_tokens(&candidate) > limit {
+            chunks.push(std::mem::take(&mut current));
+            current = word.to_string();
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Split every input exceeding `model`'s token limit into whitespace-aligned chunks,
+/// flattening the result into the order the wire request will send it in.
+pub(crate) fn prepare_inputs(model: &str, inputs: &[String]) -> Vec<String> {
+    let limit = tokenizer::max_tokens(model);
+    inputs
+        .iter()
+        .flat_map(|input| split_to_fit(input, limit))
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+}
+
+pub(crate) fn build_request<'a>(
+    model: &'a str,
+    inputs: &'a [String],
+    dimensions: Option<u32>,
+) -> EmbeddingsRequest<'a> {
+    EmbeddingsRequest {
+        model,
+        input: inputs.iter().map(String::as_str).collect(),
+        dimensions,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EmbeddingsResponse {
+    #[serde(default)]
+    data: Vec<EmbeddingDatum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingDatum {
+    embedding: Vec<f32>,
+}
+
+pub(crate) fn parse_response(response: EmbeddingsResponse) -> Vec<Vec<f32>> {
+    response.data.into_iter().map(|d| d.embedding).collect()
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_to_fit_keeps_short_text_whole() {
+        let chunks = split_to_fit("hello world", 100);
+        assert_eq!(chunks, vec!["hello world".to_string()]);
+    }
+
+    #[test]
+    fn test_split_to_fit_breaks_oversized_text_on_word_boundaries() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = split_to_fit(text, 3);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(tokenizer::count_tokens(chunk) <= 3 || !chunk.contains(' '));
+        }
+        assert_eq!(chunks.join(" "), text);
+    }
+
+    #[test]
+    fn test_prepare_inputs_flattens_split_chunks() {
+        let inputs = vec!["short".to_string(), "one two three four five".to_string()];
+        let prepared = prepare_inputs("text-embedding-3-small", &inputs);
+        assert!(prepared.len() >= inputs.len());
+    }
+}
@@ -29,8 +29,32 @@
 //! }
 //! ```
 
+use std::time::Duration;
 use thiserror::Error;
 
+#[cfg(feature = "trace")]
+use tracing_error::SpanTrace;
+
+// ============================================================================
+// SPAN TRACE (feature = "trace")
+// ============================================================================
+
+/// Span trace captured at the point an [`Error`] is constructed, used to tell which
+/// tool call or which turn of a multi-step agent loop produced the failure. Compiles
+/// down to a zero-sized `()` when the `trace` feature is disabled, so `Error` stays
+/// cheap by default.
+#[cfg(feature = "trace")]
+type Trace = Option<Box<SpanTrace>>;
+#[cfg(not(feature = "trace"))]
+type Trace = ();
+
+#[cfg(feature = "trace")]
+fn capture_trace() -> Trace {
+    Some(Box::new(SpanTrace::capture()))
+}
+#[cfg(not(feature = "trace"))]
+fn capture_trace() -> Trace {}
+
 // ============================================================================
 // TYPE ALIASES
 // ============================================================================
@@ -77,9 +101,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 ///
 /// ## Automatic Conversions
 ///
-/// The `#[from]` attribute on `Http` and `Json` variants enables automatic conversion
-/// from `reqwest::Error` and `serde_json::Error` using the `?` operator, making
-/// error propagation seamless.
+/// `From<reqwest::Error>` and `From<serde_json::Error>` enable automatic conversion
+/// into `Http` and `Json` using the `?` operator, making error propagation seamless.
+/// These are hand-written rather than `#[from]`-derived so that, with the `trace`
+/// feature enabled, they can capture a span trace at the moment of conversion just
+/// like the other constructors.
 #[derive(Error, Debug)]
 pub enum Error {
     /// HTTP request failed due to network issues, connection problems, or HTTP errors.
@@ -98,7 +124,7 @@ pub enum Error {
     /// let response = client.post(url).send().await?; // Auto-converts reqwest::Error
     /// ```
     #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[source] reqwest::Error, Trace),
 
     /// JSON serialization or deserialization failed.
     ///
@@ -114,7 +140,7 @@ pub enum Error {
     /// let value: MyType = serde_json::from_str(json_str)?; // Auto-converts serde_json::Error
     /// ```
     #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+    Json(#[source] serde_json::Error, Trace),
 
     /// Invalid configuration provided when building AgentOptions.
     ///
@@ -131,7 +157,7 @@ pub enum Error {
     /// return Err(Error::config("base_url is required"));
     /// ```
     #[error("Invalid configuration: {0}")]
-    Config(String),
+    Config(String, Trace),
 
     /// Error response received from the model server's API.
     ///
@@ -143,13 +169,50 @@ pub enum Error {
     /// - Server-side errors (500, 502, 503)
     /// - Invalid request format
     ///
+    /// `status` is the HTTP status code (`0` if unknown, e.g. from [`Error::api`]), and
+    /// `code` is the provider's machine-readable error code string, when one was present
+    /// in the response body. Use the `is_*` classification methods (e.g.
+    /// [`Error::is_rate_limited`]) rather than matching on `status` directly. `source` is
+    /// set when [`Error::api_with`] was used to attach the underlying cause (e.g. the
+    /// JSON parse error hit while decoding the error body).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// return Err(Error::api_status(404, "Model 'gpt-4' not found on server"));
+    /// ```
+    #[error("API error ({status}): {message}")]
+    Api {
+        status: u16,
+        message: String,
+        code: Option<String>,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+        trace: Trace,
+    },
+
+    /// The server rejected the request due to rate limiting (HTTP 429, or 503 with a
+    /// `Retry-After` header).
+    ///
+    /// `retry_after` is how long the server asked the caller to wait, parsed from the
+    /// `Retry-After` header in either form it's allowed to take (an integer number of
+    /// seconds, or an HTTP-date), with negative durations clamped to zero. It's `None`
+    /// when the response didn't include the header. [`crate::retry::retry_with`] honors
+    /// this delay instead of its own backoff schedule when one is present.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// return Err(Error::api("Model 'gpt-4' not found on server"));
+    /// if let Err(Error::RateLimited { retry_after: Some(d), .. }) = result {
+    ///     tokio::time::sleep(d).await;
+    /// }
     /// ```
-    #[error("API error: {0}")]
-    Api(String),
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+        trace: Trace,
+    },
 
     /// Error occurred while processing the streaming response.
     ///
@@ -160,13 +223,21 @@ pub enum Error {
     /// - Unexpected end of stream
     /// - Invalid chunk format
     ///
+    /// `source` is set when [`Error::stream_with`] was used to attach the underlying
+    /// cause (e.g. the I/O error that interrupted the connection).
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// return Err(Error::stream("Unexpected end of SSE stream"));
     /// ```
-    #[error("Streaming error: {0}")]
-    Stream(String),
+    #[error("Streaming error: {message}")]
+    Stream {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+        trace: Trace,
+    },
 
     /// Tool execution or registration failed.
     ///
@@ -177,13 +248,21 @@ pub enum Error {
     /// - Tool not found when executing
     /// - Invalid tool schema
     ///
+    /// `source` is set when [`Error::tool_with`] was used to attach the underlying
+    /// cause (e.g. the error returned by a tool handler's own fallible body).
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// return Err(Error::tool("Tool 'calculator' not found"));
     /// ```
-    #[error("Tool execution error: {0}")]
-    Tool(String),
+    #[error("Tool execution error: {message}")]
+    Tool {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+        trace: Trace,
+    },
 
     /// Invalid input provided by the user.
     ///
@@ -199,7 +278,7 @@ pub enum Error {
     /// return Err(Error::invalid_input("Prompt cannot be empty"));
     /// ```
     #[error("Invalid input: {0}")]
-    InvalidInput(String),
+    InvalidInput(String, Trace),
 
     /// Request exceeded the configured timeout duration.
     ///
@@ -212,20 +291,48 @@ pub enum Error {
     /// return Err(Error::timeout());
     /// ```
     #[error("Request timeout")]
-    Timeout,
+    Timeout(Trace),
 
     /// Miscellaneous error that doesn't fit other categories.
     ///
     /// Catch-all variant for unexpected errors or edge cases that don't fit
     /// into the specific categories above. Should be used sparingly.
     ///
+    /// `source` is set when [`Error::other_with`] was used to attach an underlying
+    /// cause, which is also how [`ResultExt::context`] wraps a foreign error.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// return Err(Error::other("Unexpected condition occurred"));
     /// ```
-    #[error("Error: {0}")]
-    Other(String),
+    #[error("Error: {message}")]
+    Other {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+        trace: Trace,
+    },
+}
+
+// ============================================================================
+// CONVERSIONS
+// ============================================================================
+
+impl From<reqwest::Error> for Error {
+    // `Trace` is `()` without the `trace` feature, which clippy reads as a suspicious
+    // "unit argument" rather than the intentional zero-cost stand-in that it is.
+    #[allow(clippy::unit_arg)]
+    fn from(source: reqwest::Error) -> Self {
+        Error::Http(source, capture_trace())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    #[allow(clippy::unit_arg)]
+    fn from(source: serde_json::Error) -> Self {
+        Error::Json(source, capture_trace())
+    }
 }
 
 // ============================================================================
@@ -255,15 +362,17 @@ impl Error {
     /// let err = Error::config("base_url must be a valid HTTP or HTTPS URL");
     /// assert_eq!(err.to_string(), "Invalid configuration: base_url must be a valid HTTP or HTTPS URL");
     /// ```
+    #[allow(clippy::unit_arg)]
     pub fn config(msg: impl Into<String>) -> Self {
-        Error::Config(msg.into())
+        Error::Config(msg.into(), capture_trace())
     }
 
-    /// Create a new API error with the server's error message.
+    /// Create a new API error with the server's error message and an unknown HTTP status.
     ///
     /// Use this when the API returns an error response (even if the HTTP request
-    /// itself succeeded). This typically happens when the server rejects the request
-    /// due to invalid parameters, missing resources, or server-side failures.
+    /// itself succeeded) but no HTTP status is available to attach, such as a
+    /// provider-specific job failure. Prefer [`Error::api_status`] when a status code is
+    /// available, so callers can classify the failure with `is_*` methods.
     ///
     /// # Arguments
     ///
@@ -275,10 +384,155 @@ impl Error {
     /// use open_agent::Error;
     ///
     /// let err = Error::api("Model 'invalid-model' not found");
-    /// assert_eq!(err.to_string(), "API error: Model 'invalid-model' not found");
+    /// assert_eq!(err.to_string(), "API error (0): Model 'invalid-model' not found");
     /// ```
     pub fn api(msg: impl Into<String>) -> Self {
-        Error::Api(msg.into())
+        Error::Api {
+            status: 0,
+            message: msg.into(),
+            code: None,
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a new API error with an unknown HTTP status, attaching `source` as the
+    /// underlying cause so it shows up in `source()` and `{:?}` cause chains.
+    ///
+    /// Use this when the failure originated from another error (e.g. the JSON parse
+    /// error hit while decoding the error response body) rather than just a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - Error message describing the failure
+    /// * `source` - The underlying error that caused this failure
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let parse_err = "not json".parse::<i32>().unwrap_err();
+    /// let err = Error::api_with("failed to parse error body", parse_err);
+    /// assert!(err.source().is_some());
+    /// ```
+    pub fn api_with(msg: impl Into<String>, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Error::Api {
+            status: 0,
+            message: msg.into(),
+            code: None,
+            source: Some(Box::new(source)),
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a new API error carrying the HTTP status code the server responded with.
+    ///
+    /// Use this whenever an HTTP status is available (the common case for a failed
+    /// request), so callers can branch on failure category with `is_not_found`,
+    /// `is_rate_limited`, `is_server_error`, and similar methods instead of string-matching
+    /// the display text.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The HTTP status code returned by the server
+    /// * `msg` - Error message from the API server (typically the response body)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    ///
+    /// let err = Error::api_status(404, "model not found");
+    /// assert!(err.is_not_found());
+    /// ```
+    pub fn api_status(status: u16, msg: impl Into<String>) -> Self {
+        Error::Api {
+            status,
+            message: msg.into(),
+            code: None,
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a rate-limited error, optionally carrying how long the server asked the
+    /// caller to wait before retrying.
+    ///
+    /// Used when the server responds 429 (or 503 with a `Retry-After` header); see
+    /// [`Error::retry_after`] for reading the delay back out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    /// use std::time::Duration;
+    ///
+    /// let err = Error::rate_limited(Some(Duration::from_secs(30)), "too many requests");
+    /// assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+    /// ```
+    pub fn rate_limited(retry_after: Option<Duration>, message: impl Into<String>) -> Self {
+        Error::RateLimited {
+            retry_after,
+            message: message.into(),
+            trace: capture_trace(),
+        }
+    }
+
+    /// Whether this is an `Api` error with HTTP status 404.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::Api { status: 404, .. })
+    }
+
+    /// Whether this is an `Api` error with HTTP status 401.
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, Error::Api { status: 401, .. })
+    }
+
+    /// Whether this is a `RateLimited` error, or an `Api` error with HTTP status 429.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Error::RateLimited { .. } | Error::Api { status: 429, .. })
+    }
+
+    /// Whether this is an `Api` error with a 5xx HTTP status.
+    pub fn is_server_error(&self) -> bool {
+        matches!(self, Error::Api { status, .. } if (500..600).contains(status))
+    }
+
+    /// Whether this is an `Api` error with a 4xx HTTP status.
+    pub fn is_client_error(&self) -> bool {
+        matches!(self, Error::Api { status, .. } if (400..500).contains(status))
+    }
+
+    /// Whether this error represents a transient failure worth retrying.
+    ///
+    /// True for [`Error::Timeout`], [`Error::RateLimited`], `Api` errors with status 429
+    /// or a 5xx status, and `Http` errors the underlying `reqwest::Error` flags as a
+    /// timeout or connection failure (refused connection, DNS failure, connect timeout).
+    /// False for `Config`, `InvalidInput`, `Json`, and any other `Api`/`Http` error, since
+    /// retrying those would just repeat the same failure.
+    ///
+    /// See [`crate::retry::retry_with`] for a driver that uses this to decide whether to
+    /// back off and try again, honoring [`Error::retry_after`] when it's set.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Timeout(_) => true,
+            Error::RateLimited { .. } => true,
+            Error::Api { status: 429, .. } => true,
+            Error::Api { status, .. } => (500..600).contains(status),
+            Error::Http(e, _) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// How long the server asked the caller to wait before retrying, if this is a
+    /// [`Error::RateLimited`] error and the response included a `Retry-After` header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
     }
 
     /// Create a new streaming error for SSE parsing or stream processing failures.
@@ -299,7 +553,40 @@ impl Error {
     /// assert_eq!(err.to_string(), "Streaming error: Unexpected end of SSE stream");
     /// ```
     pub fn stream(msg: impl Into<String>) -> Self {
-        Error::Stream(msg.into())
+        Error::Stream {
+            message: msg.into(),
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a new streaming error, attaching `source` as the underlying cause so it
+    /// shows up in `source()` and `{:?}` cause chains.
+    ///
+    /// Use this when the stream failure originated from another error, such as the I/O
+    /// error that interrupted the connection mid-stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - Description of the streaming failure
+    /// * `source` - The underlying error that caused this failure
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let parse_err = "not json".parse::<i32>().unwrap_err();
+    /// let err = Error::stream_with("failed to parse SSE chunk", parse_err);
+    /// assert!(err.source().is_some());
+    /// ```
+    pub fn stream_with(msg: impl Into<String>, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Error::Stream {
+            message: msg.into(),
+            source: Some(Box::new(source)),
+            trace: capture_trace(),
+        }
     }
 
     /// Create a new tool execution error.
@@ -320,7 +607,40 @@ impl Error {
     /// assert_eq!(err.to_string(), "Tool execution error: Calculator tool failed: division by zero");
     /// ```
     pub fn tool(msg: impl Into<String>) -> Self {
-        Error::Tool(msg.into())
+        Error::Tool {
+            message: msg.into(),
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a new tool execution error, attaching `source` as the underlying cause so
+    /// it shows up in `source()` and `{:?}` cause chains.
+    ///
+    /// Use this when a tool handler returns its own error and you want to preserve it
+    /// rather than flattening it to a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - Description of the tool failure
+    /// * `source` - The underlying error that caused this failure
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let parse_err = "not json".parse::<i32>().unwrap_err();
+    /// let err = Error::tool_with("calculator tool failed", parse_err);
+    /// assert!(err.source().is_some());
+    /// ```
+    pub fn tool_with(msg: impl Into<String>, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Error::Tool {
+            message: msg.into(),
+            source: Some(Box::new(source)),
+            trace: capture_trace(),
+        }
     }
 
     /// Create a new invalid input error for user input validation failures.
@@ -340,8 +660,9 @@ impl Error {
     /// let err = Error::invalid_input("Prompt cannot be empty");
     /// assert_eq!(err.to_string(), "Invalid input: Prompt cannot be empty");
     /// ```
+    #[allow(clippy::unit_arg)]
     pub fn invalid_input(msg: impl Into<String>) -> Self {
-        Error::InvalidInput(msg.into())
+        Error::InvalidInput(msg.into(), capture_trace())
     }
 
     /// Create a new miscellaneous error for cases that don't fit other categories.
@@ -362,7 +683,40 @@ impl Error {
     /// assert_eq!(err.to_string(), "Error: Unexpected internal state");
     /// ```
     pub fn other(msg: impl Into<String>) -> Self {
-        Error::Other(msg.into())
+        Error::Other {
+            message: msg.into(),
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+
+    /// Create a new miscellaneous error, attaching `source` as the underlying cause so
+    /// it shows up in `source()` and `{:?}` cause chains.
+    ///
+    /// This is what [`ResultExt::context`] uses under the hood to wrap a foreign error
+    /// with a human-readable layer while preserving the original as the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - Description of the error
+    /// * `source` - The underlying error that caused this failure
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use open_agent::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let parse_err = "not json".parse::<i32>().unwrap_err();
+    /// let err = Error::other_with("failed to parse retry count", parse_err);
+    /// assert!(err.source().is_some());
+    /// ```
+    pub fn other_with(msg: impl Into<String>, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Error::Other {
+            message: msg.into(),
+            source: Some(Box::new(source)),
+            trace: capture_trace(),
+        }
     }
 
     /// Create a timeout error indicating the operation exceeded the time limit.
@@ -378,8 +732,110 @@ impl Error {
     /// let err = Error::timeout();
     /// assert_eq!(err.to_string(), "Request timeout");
     /// ```
+    #[allow(clippy::unit_arg)]
     pub fn timeout() -> Self {
-        Error::Timeout
+        Error::Timeout(capture_trace())
+    }
+
+    /// The span trace captured when this error was constructed, if the `trace` feature
+    /// is enabled and the point of construction was inside an instrumented span.
+    ///
+    /// Pair with `#[tracing::instrument]` on tool handlers and request-sending
+    /// functions to see which tool call or which turn of a multi-step agent loop
+    /// produced the failure.
+    #[cfg(feature = "trace")]
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        match self {
+            Error::Http(_, trace) | Error::Json(_, trace) | Error::Config(_, trace) | Error::InvalidInput(_, trace) | Error::Timeout(trace) => {
+                trace.as_deref()
+            }
+            Error::Api { trace, .. }
+            | Error::RateLimited { trace, .. }
+            | Error::Stream { trace, .. }
+            | Error::Tool { trace, .. }
+            | Error::Other { trace, .. } => trace.as_deref(),
+        }
+    }
+}
+
+// ============================================================================
+// HTTP RESPONSE CLASSIFICATION
+// ============================================================================
+
+/// Turn a failed (`!status().is_success()`) HTTP response into the right [`Error`]
+/// variant, consuming the body as the error message.
+///
+/// Returns [`Error::RateLimited`] for a 429, or a 503 that carries a `Retry-After`
+/// header; otherwise returns [`Error::Api`] with the status, and with `code` taken from an
+/// OpenAI-style `{"error": {"code": "..."}}` body when the body has one.
+pub(crate) async fn error_from_response(response: reqwest::Response) -> Error {
+    let status = response.status().as_u16();
+    let retry_after = parse_retry_after(response.headers());
+    let message = response.text().await.unwrap_or_default();
+
+    if status == 429 || (status == 503 && retry_after.is_some()) {
+        Error::rate_limited(retry_after, message)
+    } else {
+        let code = serde_json::from_str::<serde_json::Value>(&message)
+            .ok()
+            .and_then(|body| body.get("error")?.get("code")?.as_str().map(str::to_string));
+        Error::Api {
+            status,
+            message,
+            code,
+            source: None,
+            trace: capture_trace(),
+        }
+    }
+}
+
+/// Parse a `Retry-After` header value, which is either an integer number of seconds or
+/// an HTTP-date, into a [`Duration`] from now. Negative durations (a date already in the
+/// past) are clamped to zero.
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let when = httpdate::parse_http_date(value).ok()?;
+    Some(when.duration_since(std::time::SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+// ============================================================================
+// CONTEXT EXTENSION TRAIT
+// ============================================================================
+
+/// Adds a `.context(msg)` combinator to any `Result` whose error type implements
+/// `std::error::Error`, wrapping it in an [`Error::Other`] that carries the original as
+/// its `source` rather than flattening it to a string.
+///
+/// # Example
+///
+/// ```rust
+/// use open_agent::ResultExt;
+///
+/// fn load_retry_count(raw: &str) -> open_agent::Result<i32> {
+///     raw.parse::<i32>().context("invalid retry count in config")
+/// }
+///
+/// let err = load_retry_count("not a number").unwrap_err();
+/// assert_eq!(err.to_string(), "Error: invalid retry count in config");
+/// ```
+pub trait ResultExt<T> {
+    /// Wrap a failed result in a new [`Error::Other`] layer with `msg`, keeping the
+    /// original error reachable via `source()`.
+    fn context(self, msg: impl Into<String>) -> Result<T>;
+}
+
+impl<T, E> ResultExt<T> for std::result::Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn context(self, msg: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::other_with(msg, e))
     }
 }
 
@@ -390,53 +846,109 @@ impl Error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::error::Error as _;
 
     #[test]
     fn test_error_config() {
         let err = Error::config("Invalid model");
-        assert!(matches!(err, Error::Config(_)));
+        assert!(matches!(err, Error::Config(_, _)));
         assert_eq!(err.to_string(), "Invalid configuration: Invalid model");
     }
 
     #[test]
     fn test_error_api() {
         let err = Error::api("500 Internal Server Error");
-        assert!(matches!(err, Error::Api(_)));
-        assert_eq!(err.to_string(), "API error: 500 Internal Server Error");
+        assert!(matches!(err, Error::Api { status: 0, .. }));
+        assert_eq!(err.to_string(), "API error (0): 500 Internal Server Error");
+    }
+
+    #[test]
+    fn test_error_api_status_classification() {
+        assert!(Error::api_status(404, "not found").is_not_found());
+        assert!(Error::api_status(401, "unauthorized").is_unauthorized());
+        assert!(Error::api_status(429, "rate limited").is_rate_limited());
+        assert!(Error::api_status(500, "server error").is_server_error());
+        assert!(Error::api_status(503, "server error").is_server_error());
+        assert!(Error::api_status(400, "bad request").is_client_error());
+        assert!(Error::api_status(404, "not found").is_client_error());
+        assert!(!Error::api_status(200, "ok").is_client_error());
+        assert!(!Error::api_status(200, "ok").is_server_error());
+    }
+
+    #[test]
+    fn test_error_is_retryable() {
+        assert!(Error::timeout().is_retryable());
+        assert!(Error::api_status(429, "rate limited").is_retryable());
+        assert!(Error::api_status(500, "server error").is_retryable());
+        assert!(Error::api_status(503, "server error").is_retryable());
+        assert!(!Error::api_status(404, "not found").is_retryable());
+        assert!(!Error::api_status(400, "bad request").is_retryable());
+        assert!(!Error::api("unknown status").is_retryable());
+        assert!(!Error::config("bad config").is_retryable());
+        assert!(!Error::invalid_input("bad input").is_retryable());
     }
 
     #[test]
     fn test_error_stream() {
         let err = Error::stream("Connection lost");
-        assert!(matches!(err, Error::Stream(_)));
+        assert!(matches!(err, Error::Stream { source: None, .. }));
         assert_eq!(err.to_string(), "Streaming error: Connection lost");
     }
 
     #[test]
     fn test_error_tool() {
         let err = Error::tool("Tool not found");
-        assert!(matches!(err, Error::Tool(_)));
+        assert!(matches!(err, Error::Tool { source: None, .. }));
         assert_eq!(err.to_string(), "Tool execution error: Tool not found");
     }
 
+    #[test]
+    fn test_error_with_variants_preserve_source_chain() {
+        let parse_err = "not a number".parse::<i32>().unwrap_err();
+        let err = Error::api_with("bad error body", parse_err);
+        assert!(err.source().is_some());
+        assert_eq!(err.to_string(), "API error (0): bad error body");
+
+        let parse_err = "not a number".parse::<i32>().unwrap_err();
+        let err = Error::stream_with("bad chunk", parse_err);
+        assert!(err.source().is_some());
+
+        let parse_err = "not a number".parse::<i32>().unwrap_err();
+        let err = Error::tool_with("tool failed", parse_err);
+        assert!(err.source().is_some());
+
+        let parse_err = "not a number".parse::<i32>().unwrap_err();
+        let err = Error::other_with("wrapped", parse_err);
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_result_ext_context_wraps_with_source() {
+        let result: std::result::Result<i32, _> = "not a number".parse::<i32>();
+        let err = result.context("failed to parse count").unwrap_err();
+        assert!(matches!(err, Error::Other { source: Some(_), .. }));
+        assert_eq!(err.to_string(), "Error: failed to parse count");
+        assert!(err.source().is_some());
+    }
+
     #[test]
     fn test_error_invalid_input() {
         let err = Error::invalid_input("Missing parameter");
-        assert!(matches!(err, Error::InvalidInput(_)));
+        assert!(matches!(err, Error::InvalidInput(_, _)));
         assert_eq!(err.to_string(), "Invalid input: Missing parameter");
     }
 
     #[test]
     fn test_error_timeout() {
         let err = Error::timeout();
-        assert!(matches!(err, Error::Timeout));
+        assert!(matches!(err, Error::Timeout(_)));
         assert_eq!(err.to_string(), "Request timeout");
     }
 
     #[test]
     fn test_error_other() {
         let err = Error::other("Something went wrong");
-        assert!(matches!(err, Error::Other(_)));
+        assert!(matches!(err, Error::Other { source: None, .. }));
         assert_eq!(err.to_string(), "Error: Something went wrong");
     }
 
@@ -446,7 +958,7 @@ mod tests {
         // This is mostly for compile-time checking
         fn _test_conversion(_e: reqwest::Error) -> Error {
             // This function just needs to compile
-            Error::Http(_e)
+            _e.into()
         }
     }
 
@@ -455,7 +967,7 @@ mod tests {
         // Test that serde_json::Error can be converted
         let json_err = serde_json::from_str::<serde_json::Value>("invalid json").unwrap_err();
         let err: Error = json_err.into();
-        assert!(matches!(err, Error::Json(_)));
+        assert!(matches!(err, Error::Json(..)));
     }
 
     #[test]
@@ -0,0 +1,149 @@
+//! # Lifecycle Hooks
+//!
+//! Hooks let callers intercept execution at key points in an agent's run loop: before a
+//! user prompt is sent, before a tool is executed, and after a tool returns. They're the
+//! extension point for security gates, audit logging, input/output rewriting, and
+//! compliance checks, without the SDK needing to know about any of that itself.
+
+use crate::types::Message;
+use std::sync::Arc;
+
+/// Identifier for the `PreToolUse` hook type.
+pub const HOOK_PRE_TOOL_USE: &str = "PreToolUse";
+/// Identifier for the `PostToolUse` hook type.
+pub const HOOK_POST_TOOL_USE: &str = "PostToolUse";
+/// Identifier for the `UserPromptSubmit` hook type.
+pub const HOOK_USER_PROMPT_SUBMIT: &str = "UserPromptSubmit";
+
+/// Decision returned by a hook to control how execution proceeds.
+#[derive(Debug, Clone, Default)]
+pub enum HookDecision {
+    /// Let execution proceed unmodified.
+    #[default]
+    Continue,
+    /// Block execution with a reason to report back to the caller.
+    Block(String),
+    /// Replace the prompt or tool input with a modified value before it continues.
+    Modify(serde_json::Value),
+}
+
+/// Event data passed to `UserPromptSubmit` hooks before sending prompts to the API.
+#[derive(Debug, Clone)]
+pub struct UserPromptSubmitEvent {
+    /// The prompt the user submitted.
+    pub prompt: String,
+    /// The full conversation history so far.
+    pub history: Vec<Message>,
+}
+
+/// Event data passed to `PreToolUse` hooks before tool execution.
+#[derive(Debug, Clone)]
+pub struct PreToolUseEvent {
+    /// Name of the tool about to be called.
+    pub tool_name: String,
+    /// JSON input the model supplied for the call.
+    pub input: serde_json::Value,
+    /// Unique ID of this tool call.
+    pub id: String,
+    /// The full conversation history so far.
+    pub history: Vec<Message>,
+}
+
+/// Event data passed to `PostToolUse` hooks after tool execution.
+#[derive(Debug, Clone)]
+pub struct PostToolUseEvent {
+    /// Name of the tool that was called.
+    pub tool_name: String,
+    /// JSON input the model supplied for the call.
+    pub input: serde_json::Value,
+    /// Unique ID of this tool call.
+    pub id: String,
+    /// The tool's result, rendered as a string.
+    pub result: String,
+    /// The full conversation history so far.
+    pub history: Vec<Message>,
+}
+
+/// A registered hook callback for lifecycle event type `E`.
+type Hook<E> = Arc<dyn Fn(&E) -> HookDecision + Send + Sync>;
+
+type UserPromptSubmitHook = Hook<UserPromptSubmitEvent>;
+type PreToolUseHook = Hook<PreToolUseEvent>;
+type PostToolUseHook = Hook<PostToolUseEvent>;
+
+/// Container for registering and managing lifecycle hooks.
+///
+/// Hooks of a given type are executed sequentially in registration order; the first one
+/// to return anything other than [`HookDecision::Continue`] short-circuits the rest.
+#[derive(Clone, Default)]
+pub struct Hooks {
+    user_prompt_submit: Vec<UserPromptSubmitHook>,
+    pre_tool_use: Vec<PreToolUseHook>,
+    post_tool_use: Vec<PostToolUseHook>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("user_prompt_submit", &self.user_prompt_submit.len())
+            .field("pre_tool_use", &self.pre_tool_use.len())
+            .field("post_tool_use", &self.post_tool_use.len())
+            .finish()
+    }
+}
+
+impl Hooks {
+    /// Create an empty hook set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a `UserPromptSubmit` hook.
+    pub fn on_user_prompt_submit(
+        mut self,
+        hook: impl Fn(&UserPromptSubmitEvent) -> HookDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.user_prompt_submit.push(Arc::new(hook));
+        self
+    }
+
+    /// Register a `PreToolUse` hook.
+    pub fn on_pre_tool_use(
+        mut self,
+        hook: impl Fn(&PreToolUseEvent) -> HookDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.pre_tool_use.push(Arc::new(hook));
+        self
+    }
+
+    /// Register a `PostToolUse` hook.
+    pub fn on_post_tool_use(
+        mut self,
+        hook: impl Fn(&PostToolUseEvent) -> HookDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.post_tool_use.push(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn run_user_prompt_submit(&self, event: &UserPromptSubmitEvent) -> HookDecision {
+        Self::run(&self.user_prompt_submit, event)
+    }
+
+    pub(crate) fn run_pre_tool_use(&self, event: &PreToolUseEvent) -> HookDecision {
+        Self::run(&self.pre_tool_use, event)
+    }
+
+    pub(crate) fn run_post_tool_use(&self, event: &PostToolUseEvent) -> HookDecision {
+        Self::run(&self.post_tool_use, event)
+    }
+
+    fn run<E>(hooks: &[Hook<E>], event: &E) -> HookDecision {
+        for hook in hooks {
+            match hook(event) {
+                HookDecision::Continue => continue,
+                decision => return decision,
+            }
+        }
+        HookDecision::Continue
+    }
+}
@@ -0,0 +1,144 @@
+//! # Image Generation
+//!
+//! Adds the generation half of the image story to match the input half already modeled
+//! by [`crate::ImageBlock`]: `Client::generate_images` POSTs to an OpenAI-compatible
+//! `/v1/images/generations` endpoint and returns the results as `ImageBlock`s, so
+//! generated images can be fed straight back into a conversation as input.
+
+use crate::error::{Error, Result};
+use crate::types::ImageBlock;
+use serde::{Deserialize, Serialize};
+
+/// Requested format for generated images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    /// Server hosts the image and returns a URL to it.
+    #[default]
+    Url,
+    /// Server returns the image inlined as base64.
+    B64Json,
+}
+
+/// Options for a single image-generation request.
+///
+/// Built using [`ImageGenerationOptions::builder`].
+#[derive(Debug, Clone)]
+pub struct ImageGenerationOptions {
+    pub(crate) prompt: String,
+    pub(crate) n: Option<u32>,
+    pub(crate) size: Option<String>,
+    pub(crate) response_format: ImageResponseFormat,
+}
+
+impl ImageGenerationOptions {
+    /// Start building options for an image-generation request.
+    pub fn builder(prompt: impl Into<String>) -> ImageGenerationOptionsBuilder {
+        ImageGenerationOptionsBuilder::new(prompt)
+    }
+}
+
+/// Builder for [`ImageGenerationOptions`].
+#[derive(Debug, Clone)]
+pub struct ImageGenerationOptionsBuilder {
+    prompt: String,
+    n: Option<u32>,
+    size: Option<String>,
+    response_format: ImageResponseFormat,
+}
+
+impl ImageGenerationOptionsBuilder {
+    fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            n: None,
+            size: None,
+            response_format: ImageResponseFormat::default(),
+        }
+    }
+
+    /// Number of images to generate.
+    pub fn n(mut self, n: u32) -> Self {
+        self.n = Some(n);
+        self
+    }
+
+    /// Requested image size, e.g. `"1024x1024"`.
+    pub fn size(mut self, size: impl Into<String>) -> Self {
+        self.size = Some(size.into());
+        self
+    }
+
+    /// Requested response format (hosted URL or inlined base64).
+    pub fn response_format(mut self, format: ImageResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// Validate and build the final [`ImageGenerationOptions`].
+    pub fn build(self) -> Result<ImageGenerationOptions> {
+        if self.prompt.is_empty() {
+            return Err(Error::invalid_input("image generation prompt cannot be empty"));
+        }
+        Ok(ImageGenerationOptions {
+            prompt: self.prompt,
+            n: self.n,
+            size: self.size,
+            response_format: self.response_format,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ImageGenerationRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<&'a str>,
+    response_format: ImageResponseFormat,
+}
+
+pub(crate) fn build_request<'a>(
+    model: &'a str,
+    options: &'a ImageGenerationOptions,
+) -> ImageGenerationRequest<'a> {
+    ImageGenerationRequest {
+        model,
+        prompt: &options.prompt,
+        n: options.n,
+        size: options.size.as_deref(),
+        response_format: options.response_format,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ImageGenerationResponse {
+    #[serde(default)]
+    data: Vec<ImageGenerationDatum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageGenerationDatum {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    b64_json: Option<String>,
+}
+
+/// Convert the raw `/v1/images/generations` response into `ImageBlock`s, reusing the same
+/// base64/URL construction paths used for image input so the data-URI logic isn't duplicated.
+pub(crate) fn parse_response(response: ImageGenerationResponse) -> Result<Vec<ImageBlock>> {
+    response
+        .data
+        .into_iter()
+        .map(|datum| match (datum.b64_json, datum.url) {
+            (Some(b64), _) => ImageBlock::from_base64(b64, "image/png"),
+            (None, Some(url)) => ImageBlock::from_url(url),
+            (None, None) => Err(Error::api(
+                "image generation response missing both url and b64_json",
+            )),
+        })
+        .collect()
+}
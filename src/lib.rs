@@ -20,6 +20,7 @@
 //! - **Lifecycle Hooks**: Intercept and control execution at key points
 //! - **Interrupts**: Gracefully cancel long-running operations
 //! - **Context Management**: Manual token estimation and history truncation
+//! - **Embeddings**: Generate embeddings with automatic token-aware input splitting
 //! - **Retry Logic**: Exponential backoff with jitter for reliability
 //!
 //! ## Two Interaction Modes
@@ -49,6 +50,9 @@
 //!             ContentBlock::Text(text_block) => {
 //!                 print!("{}", text_block.text);
 //!             }
+//!             ContentBlock::Image(_) => {
+//!                 // Images are rarely part of a model's own response
+//!             }
 //!             ContentBlock::ToolUse(tool_block) => {
 //!                 println!("Tool called: {}", tool_block.name);
 //!             }
@@ -78,7 +82,7 @@
 //!         .build()?;
 //!
 //!     // Create a stateful client that maintains conversation history
-//!     let mut client = Client::new(options);
+//!     let mut client = Client::new(options)?;
 //!
 //!     // First turn
 //!     client.send("What's 2+2?").await?;
@@ -111,7 +115,11 @@
 //! - **config**: Provider-specific configuration helpers
 //! - **error**: Comprehensive error types and conversions
 //! - **context**: Token estimation and message truncation utilities
+//! - **embeddings**: Embeddings API surface with automatic input splitting
+//! - **tokenizer**: Local BPE tokenizer backing `count_tokens` and the embeddings splitter
+//! - **polling**: Async polling for job-based backends that return a prediction handle
 //! - **retry**: Exponential backoff retry logic with jitter
+//! - **upload**: Multipart image upload to a configurable media endpoint
 //! - **utils**: Internal utilities for SSE parsing and tool aggregation
 
 // ============================================================================
@@ -133,6 +141,10 @@ mod config;
 /// Provides manual control over conversation memory to prevent context overflow.
 mod context;
 
+/// Embeddings API surface for OpenAI-compatible `/v1/embeddings` endpoints, with
+/// automatic splitting of inputs that exceed the model's token limit.
+mod embeddings;
+
 /// Error types and conversions for comprehensive error handling throughout the SDK.
 /// Defines the `Error` enum and `Result<T>` type alias used across all public APIs.
 mod error;
@@ -141,14 +153,30 @@ mod error;
 /// Enables security gates, audit logging, input/output modification, and compliance checks.
 mod hooks;
 
+/// Image generation API surface for OpenAI-compatible `/v1/images/generations` endpoints.
+/// Returns generated images as the same `ImageBlock` type used for image input.
+mod images;
+
+/// Async polling for job-based backends that hand back a prediction handle instead of
+/// completing synchronously. Used by `Client::send` and `Client::generate_images`.
+mod polling;
+
 /// Tool definition and execution system with automatic JSON schema generation.
 /// Allows LLMs to call Rust functions with type-safe parameter handling.
 mod tools;
 
+/// Local BPE tokenizer for estimating token counts ahead of sending a request, used by
+/// the embeddings module to budget and split oversized inputs.
+mod tokenizer;
+
 /// Core type definitions for messages, content blocks, and agent configuration.
 /// Includes builder patterns for ergonomic configuration and OpenAI API serialization.
 mod types;
 
+/// Multipart image upload to a configurable media endpoint, used by
+/// `Client::upload_image` and `Client::send`'s automatic large-image replacement.
+mod upload;
+
 /// Internal utilities for Server-Sent Events (SSE) parsing and tool call aggregation.
 /// Handles the low-level details of streaming response parsing.
 mod utils;
@@ -165,148 +193,50 @@ pub mod retry;
 
 // --- Core Client API ---
 
-pub use client::{
-    /// Stateful multi-turn conversation client with automatic history management.
-    /// Use this when you need to maintain conversation context across multiple turns.
-    /// Supports tool execution, interrupts, and lifecycle hooks.
-    Client,
-
-    /// Simple single-turn query function that returns a stream of content blocks.
-    /// Use this for one-off queries without conversation state.
-    /// Returns a ContentStream that yields ContentBlock items as they arrive.
-    query,
-};
+pub use client::{Client, query};
 
 // --- Provider Configuration ---
 
-pub use config::{
-    /// Enum representing supported LLM providers (LMStudio, Ollama, LlamaCpp, VLLM).
-    /// Used to get default base URLs and model names for each provider.
-    Provider,
-
-    /// Get the base URL for API requests, with support for environment variable overrides.
-    /// Priority: environment variable > provider default > fallback parameter.
-    get_base_url,
-
-    /// Get the model name for requests, with optional environment variable override.
-    /// Priority: environment variable (if prefer_env=true) > fallback parameter.
-    get_model,
-};
+pub use config::{Provider, get_base_url, get_model};
 
 // --- Context Management ---
 
 pub use context::{
-    /// Estimate the number of tokens in a message history using a character-based approximation.
-    /// Approximation: ~1 token per 4 characters (70-85% accurate across model families).
-    estimate_tokens,
+    EvictionPolicy, estimate_tokens, estimate_tokens_precise, is_approaching_limit, truncate_messages,
+};
 
-    /// Check if a message history is approaching a token limit.
-    /// Returns true if estimated tokens exceed the limit. Useful for proactive truncation.
-    is_approaching_limit,
+// --- Tokenization ---
 
-    /// Truncate message history to keep only the most recent turns.
-    /// Can optionally preserve the system message regardless of turn count.
-    truncate_messages,
-};
+pub use tokenizer::{count_tokens, max_tokens};
 
 // --- Error Handling ---
 
-pub use error::{
-    /// Comprehensive error type covering HTTP, JSON, API, streaming, and configuration errors.
-    /// Implements std::error::Error and provides detailed error context.
-    Error,
-
-    /// Type alias for Result<T, Error> used throughout the SDK.
-    /// Makes error handling more concise in client code.
-    Result,
-};
+pub use error::{Error, Result, ResultExt};
 
 // --- Lifecycle Hooks ---
 
 pub use hooks::{
-    /// Constant string identifier for the PreToolUse hook type.
-    /// Used internally for hook registration and logging.
-    HOOK_PRE_TOOL_USE,
-
-    /// Constant string identifier for the PostToolUse hook type.
-    /// Used internally for hook registration and logging.
-    HOOK_POST_TOOL_USE,
-
-    /// Constant string identifier for the UserPromptSubmit hook type.
-    /// Used internally for hook registration and logging.
-    HOOK_USER_PROMPT_SUBMIT,
-
-    /// Decision object returned by hooks to control execution flow.
-    /// Can continue, block, or modify inputs/prompts during lifecycle events.
-    HookDecision,
-
-    /// Container for registering and managing lifecycle hooks.
-    /// Hooks are executed sequentially with the first non-None decision taking effect.
-    Hooks,
-
-    /// Event data passed to PostToolUse hooks after tool execution.
-    /// Contains tool name, input, ID, result, and full conversation history.
-    PostToolUseEvent,
-
-    /// Event data passed to PreToolUse hooks before tool execution.
-    /// Contains tool name, input, ID, and full conversation history.
-    PreToolUseEvent,
-
-    /// Event data passed to UserPromptSubmit hooks before sending prompts to the API.
-    /// Contains the user prompt and full conversation history.
-    UserPromptSubmitEvent,
+    HOOK_POST_TOOL_USE, HOOK_PRE_TOOL_USE, HOOK_USER_PROMPT_SUBMIT, HookDecision, Hooks,
+    PostToolUseEvent, PreToolUseEvent, UserPromptSubmitEvent,
 };
 
 // --- Tool System ---
 
-pub use tools::{
-    /// Tool definition with name, description, JSON schema, and async handler.
-    /// Created using ToolBuilder or the tool() convenience function.
-    Tool,
-
-    /// Builder for constructing tools with fluent parameter definition.
-    /// Automatically generates JSON schema from parameter types.
-    ToolBuilder,
-
-    /// Convenience function to start building a tool with name and description.
-    /// Returns a ToolBuilder for adding parameters and handler.
-    tool,
-};
-
-// --- Core Types ---
-
-pub use types::{
-    /// Configuration options for agents, built using the builder pattern.
-    /// Contains system prompt, model, base URL, tools, hooks, and execution settings.
-    AgentOptions,
-
-    /// Builder for constructing AgentOptions with type-safe validation.
-    /// Required fields: system_prompt, model, base_url.
-    AgentOptionsBuilder,
+pub use tools::{Tool, ToolBuilder, tool};
 
-    /// Enum representing a unit of content in a message (Text, ToolUse, or ToolResult).
-    /// Messages can contain multiple content blocks of different types.
-    ContentBlock,
+// --- Image Generation ---
 
-    /// A single message in a conversation with a role and content blocks.
-    /// Used to build conversation history and communicate with the LLM.
-    Message,
+pub use images::{ImageGenerationOptions, ImageGenerationOptionsBuilder, ImageResponseFormat};
 
-    /// Role of a message participant (System, User, Assistant, or Tool).
-    /// Determines how the LLM interprets the message content.
-    MessageRole,
+// --- Embeddings ---
 
-    /// Content block containing plain text generated by the model or provided by the user.
-    /// Contains a single text field with the content.
-    TextBlock,
+pub use embeddings::{EmbeddingOptions, EmbeddingOptionsBuilder};
 
-    /// Content block containing the result of a tool execution.
-    /// Includes the tool use ID, content (success or error), and optional error flag.
-    ToolResultBlock,
+// --- Core Types ---
 
-    /// Content block representing a tool call made by the model.
-    /// Contains tool name, unique ID, and JSON input parameters.
-    ToolUseBlock,
+pub use types::{
+    AgentOptions, AgentOptionsBuilder, ContentBlock, ContentEncoding, ImageBlock, ImageDetail, Message,
+    MessageRole, OpenAIContent, OpenAIContentPart, TextBlock, ToolResultBlock, ToolUseBlock,
 };
 
 // ============================================================================
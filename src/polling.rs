@@ -0,0 +1,93 @@
+//! # Async Job Polling
+//!
+//! Some OpenAI-compatible providers don't complete synchronously (or stream); instead
+//! they hand back a prediction/job object shaped like `{ "status": "...", "urls": {
+//! "get": "...", "stream": "..." } }` and expect the caller to poll `urls.get` until
+//! `status` becomes `succeeded` (with the result in `output`) or `failed`. [`job_urls`]
+//! recognizes that shape in an already-parsed response body, and
+//! [`poll_until_complete`] drives the polling loop on [`crate::Client::send`]'s and
+//! [`crate::Client::generate_images`]'s behalf, so callers targeting job-based backends
+//! don't need to handle the handle-and-poll dance themselves.
+//!
+//! The job's `output` is assumed to already be shaped like the synchronous response the
+//! caller expects (e.g. the same `{"data": [...]}` body `/v1/images/generations` would
+//! have returned directly), since there's no generic way to normalize arbitrary
+//! provider-specific output formats.
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+struct JobResponse {
+    status: String,
+    #[serde(default)]
+    output: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// If `value` looks like a job handle (has a `urls.get` string field), return that URL.
+/// Returns `None` for an ordinary synchronous response body.
+pub(crate) fn job_urls(value: &serde_json::Value) -> Option<String> {
+    value.get("urls")?.get("get")?.as_str().map(str::to_string)
+}
+
+/// Poll `get_url` every `interval` until the job's status is `succeeded` (returning its
+/// `output`) or `failed` (returning an error), giving up with [`Error::timeout`] if
+/// `timeout` elapses first.
+pub(crate) async fn poll_until_complete(
+    http: &reqwest::Client,
+    get_url: &str,
+    interval: Duration,
+    timeout: Duration,
+) -> Result<serde_json::Value> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        let response = http.get(get_url).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::error::error_from_response(response).await);
+        }
+
+        let job: JobResponse = response.json().await?;
+        match job.status.as_str() {
+            "succeeded" => {
+                return job
+                    .output
+                    .ok_or_else(|| Error::api("job succeeded but response had no output"));
+            }
+            "failed" => {
+                return Err(Error::api(job.error.unwrap_or_else(|| "job failed".to_string())));
+            }
+            _ => {
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(Error::timeout());
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_job_urls_detects_handle_shape() {
+        let value = json!({ "status": "starting", "urls": { "get": "https://api.example.com/p/1" } });
+        assert_eq!(job_urls(&value).as_deref(), Some("https://api.example.com/p/1"));
+    }
+
+    #[test]
+    fn test_job_urls_none_for_ordinary_response() {
+        let value = json!({ "data": [{ "url": "https://example.com/a.png" }] });
+        assert_eq!(job_urls(&value), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! # Retry with Backoff
+//!
+//! A small driver for retrying a fallible async operation against a flaky local or
+//! remote model server, using [`Error::is_retryable`] to decide whether a failure is
+//! worth retrying at all. [`retry_with`] loops an operation under a [`RetryPolicy`],
+//! sleeping an exponentially increasing delay (optionally full-jittered) between
+//! attempts, and gives up once `max_attempts` is reached or the error isn't retryable.
+
+use crate::error::Result;
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+/// Default number of attempts before giving up (the first try plus two retries).
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry.
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Default ceiling on the backoff delay, regardless of attempt count.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Configuration for [`retry_with`]'s backoff behavior.
+///
+/// The delay before attempt `n` (for `n > 1`) is `base_delay * 2^(n-2)`, capped at
+/// `max_delay`. When `jitter` is true, that capped delay is treated as an upper bound
+/// and the actual sleep is chosen uniformly from `[0, delay]` ("full jitter"), which
+/// spreads out retries from many clients that failed at the same moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+        }
+    }
+}
+
+/// The backoff delay after the given 1-indexed attempt number has failed (that is, before
+/// attempt `attempt + 1`), before jitter is applied.
+fn capped_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let multiplier = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+    policy
+        .base_delay
+        .checked_mul(multiplier)
+        .unwrap_or(policy.max_delay)
+        .min(policy.max_delay)
+}
+
+/// Retry `op` under `policy` until it succeeds, a non-retryable error occurs (see
+/// [`Error::is_retryable`]), or `policy.max_attempts` attempts have been made.
+///
+/// When a failure carries an [`Error::retry_after`] delay (a rate-limited response that
+/// included a `Retry-After` header), that delay is honored in place of `policy`'s own
+/// backoff schedule, since the server knows better than we do how long to wait.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use open_agent::retry::{retry_with, RetryPolicy};
+///
+/// let response = retry_with(RetryPolicy::default(), || client.send("ping")).await?;
+/// ```
+pub async fn retry_with<T, F, Fut>(policy: RetryPolicy, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < policy.max_attempts && err.is_retryable() => {
+                let delay = match err.retry_after() {
+                    Some(server_delay) => server_delay,
+                    None => {
+                        let delay = capped_delay(&policy, attempt);
+                        if policy.jitter {
+                            Duration::from_millis(rand::thread_rng().gen_range(0..=delay.as_millis() as u64))
+                        } else {
+                            delay
+                        }
+                    }
+                };
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+            jitter: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_succeeds_immediately() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with(fast_policy(3), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, Error>(42)
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_retries_retryable_errors_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with(fast_policy(5), || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            if n < 2 {
+                Err(Error::timeout())
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with(fast_policy(3), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(Error::timeout())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_short_circuits_non_retryable_errors() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with(fast_policy(5), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(Error::invalid_input("bad input"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_honors_server_retry_after() {
+        let calls = AtomicU32::new(0);
+        let start = std::time::Instant::now();
+        let result = retry_with(fast_policy(3), || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            if n == 0 {
+                Err(Error::rate_limited(Some(Duration::from_millis(20)), "slow down"))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,182 @@
+//! # BPE Tokenizer
+//!
+//! A tiktoken-style byte-pair-encoding tokenizer used for local token counting ahead of
+//! sending a request, so [`crate::Client::embed`] can split oversized inputs before they
+//! hit the wire. As in `cl100k_base`, text is first split into candidate chunks with a
+//! regex, then each chunk is repeatedly merged byte-pair by byte-pair - always picking the
+//! adjacent pair with the lowest rank - until no mergeable pair remains; the token count is
+//! the number of pieces left standing.
+//!
+//! Unlike `cl100k_base`, the merge ranks here are trained locally (once, lazily, at first
+//! use) from a small embedded English corpus rather than loaded from OpenAI's published
+//! rank file, since this crate has no network access to fetch it. Token counts are
+//! therefore a useful local approximation for budgeting, not a byte-exact match to
+//! OpenAI's own tokenizer.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Embedded training corpus the local BPE vocabulary is learned from.
+const TRAINING_CORPUS: &str = include_str!("tokenizer_corpus.txt");
+
+/// Number of merge rules to learn. Higher values produce coarser (more token-dense)
+/// pieces for common English substrings at the cost of a slower one-time training pass.
+const MERGE_COUNT: usize = 400;
+
+/// The GPT-2/cl100k-style pre-tokenization pattern: contractions, runs of letters, runs
+/// of digits, runs of other non-whitespace, and whitespace, each optionally led by a
+/// single leading space so the space is absorbed into the following piece.
+const SPLIT_PATTERN: &str =
+    r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+";
+
+struct Bpe {
+    ranks: HashMap<Vec<u8>, u32>,
+    pattern: Regex,
+}
+
+fn bpe() -> &'static Bpe {
+    static INSTANCE: OnceLock<Bpe> = OnceLock::new();
+    INSTANCE.get_or_init(|| Bpe::train(TRAINING_CORPUS, MERGE_COUNT))
+}
+
+impl Bpe {
+    fn train(corpus: &str, merge_count: usize) -> Self {
+        let pattern = Regex::new(SPLIT_PATTERN).expect("SPLIT_PATTERN is a valid regex");
+
+        let mut ranks: HashMap<Vec<u8>, u32> =
+            (0u32..256).map(|b| (vec![b as u8], b)).collect();
+        let mut next_rank = 256u32;
+
+        let mut words: Vec<Vec<Vec<u8>>> = pattern
+            .find_iter(corpus)
+            .map(|m| m.as_str().bytes().map(|b| vec![b]).collect())
+            .collect();
+
+        for _ in 0..merge_count {
+            let mut pair_counts: HashMap<(Vec<u8>, Vec<u8>), usize> = HashMap::new();
+            for word in &words {
+                for pair in word.windows(2) {
+                    *pair_counts.entry((pair[0].clone(), pair[1].clone())).or_insert(0) += 1;
+                }
+            }
+
+            let Some(((a, b), _)) = pair_counts.into_iter().max_by_key(|(_, count)| *count) else {
+                break;
+            };
+
+            let mut merged = a.clone();
+            merged.extend_from_slice(&b);
+            ranks.entry(merged.clone()).or_insert_with(|| {
+                let rank = next_rank;
+                next_rank += 1;
+                rank
+            });
+
+            for word in &mut words {
+                *word = merge_adjacent(word, &a, &b, &merged);
+            }
+        }
+
+        Self { ranks, pattern }
+    }
+
+    /// Apply the trained merge rules to one pre-split chunk, repeatedly merging the
+    /// lowest-ranked adjacent pair until none remain.
+    fn encode_chunk(&self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        let mut pieces: Vec<Vec<u8>> = chunk.iter().map(|&b| vec![b]).collect();
+
+        loop {
+            let mut best: Option<(usize, u32)> = None;
+            for i in 0..pieces.len().saturating_sub(1) {
+                let mut candidate = pieces[i].clone();
+                candidate.extend_from_slice(&pieces[i + 1]);
+                if let Some(&rank) = self.ranks.get(&candidate) {
+                    if best.is_none_or(|(_, best_rank)| rank < best_rank) {
+                        best = Some((i, rank));
+                    }
+                }
+            }
+
+            let Some((i, _)) = best else { break };
+            let mut merged = pieces[i].clone();
+            merged.extend_from_slice(&pieces[i + 1]);
+            pieces.splice(i..=i + 1, [merged]);
+        }
+
+        pieces
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.pattern
+            .find_iter(text)
+            .map(|m| self.encode_chunk(m.as_str().as_bytes()).len())
+            .sum()
+    }
+}
+
+/// Merge every consecutive occurrence of `(a, b)` in `word` into `merged`.
+fn merge_adjacent(word: &[Vec<u8>], a: &[u8], b: &[u8], merged: &[u8]) -> Vec<Vec<u8>> {
+    let mut out = Vec::with_capacity(word.len());
+    let mut i = 0;
+    while i < word.len() {
+        if i + 1 < word.len() && word[i] == a && word[i + 1] == b {
+            out.push(merged.to_vec());
+            i += 2;
+        } else {
+            out.push(word[i].clone());
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Estimate the number of BPE tokens `text` would encode to.
+pub fn count_tokens(text: &str) -> usize {
+    bpe().count(text)
+}
+
+/// The maximum input tokens accepted by a given embedding model.
+///
+/// Every embeddings model shipped by OpenAI-compatible servers to date (`ada-002` and the
+/// `text-embedding-3-*` family) shares the same 8191-token input limit, so unrecognized
+/// model names fall back to it too rather than refusing to budget at all.
+pub fn max_tokens(model: &str) -> usize {
+    match model {
+        "text-embedding-ada-002" | "text-embedding-3-small" | "text-embedding-3-large" => 8191,
+        _ => 8191,
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_tokens_nonzero_for_nonempty_text() {
+        assert!(count_tokens("hello, world!") > 0);
+    }
+
+    #[test]
+    fn test_count_tokens_zero_for_empty_text() {
+        assert_eq!(count_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_count_tokens_grows_with_input_length() {
+        let short = count_tokens("the quick brown fox");
+        let long = count_tokens(&"the quick brown fox ".repeat(50));
+        assert!(long > short);
+    }
+
+    #[test]
+    fn test_max_tokens_known_and_unknown_models() {
+        assert_eq!(max_tokens("text-embedding-ada-002"), 8191);
+        assert_eq!(max_tokens("text-embedding-3-large"), 8191);
+        assert_eq!(max_tokens("some-unrecognized-model"), 8191);
+    }
+}
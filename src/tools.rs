@@ -0,0 +1,129 @@
+//! # Tool Definition System
+//!
+//! Tools let a registered Rust closure be exposed to the model as a callable function.
+//! [`ToolBuilder`] accumulates a JSON-schema `parameters` object field by field so callers
+//! don't have to hand-write schema JSON, then attaches an async handler that receives the
+//! model's JSON input and returns a string result.
+
+use crate::error::Result;
+use serde_json::{Map, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Boxed async tool handler: takes the model's JSON input, returns a string result.
+pub(crate) type ToolHandler =
+    Arc<dyn Fn(Value) -> Pin<Box<dyn Future<Output = Result<String>> + Send>> + Send + Sync>;
+
+/// A tool definition with name, description, JSON schema, and async handler.
+///
+/// Created using [`ToolBuilder`] or the [`tool`] convenience function.
+#[derive(Clone)]
+pub struct Tool {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) parameters: Value,
+    pub(crate) handler: ToolHandler,
+}
+
+impl std::fmt::Debug for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tool")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("parameters", &self.parameters)
+            .finish()
+    }
+}
+
+impl Tool {
+    /// The tool's name, as advertised to the model.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The tool's description, as advertised to the model.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The JSON schema for this tool's parameters.
+    pub fn parameters(&self) -> &Value {
+        &self.parameters
+    }
+
+    /// Invoke the tool's handler with the model-supplied JSON input.
+    pub async fn call(&self, input: Value) -> Result<String> {
+        (self.handler)(input).await
+    }
+}
+
+/// Builder for constructing [`Tool`]s with fluent parameter definition.
+///
+/// Each call to [`ToolBuilder::param`] adds one property to the generated JSON schema.
+pub struct ToolBuilder {
+    name: String,
+    description: String,
+    properties: Map<String, Value>,
+    required: Vec<String>,
+}
+
+impl ToolBuilder {
+    fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            properties: Map::new(),
+            required: Vec::new(),
+        }
+    }
+
+    /// Add a parameter to the tool's JSON schema.
+    ///
+    /// `json_type` is the JSON-schema `type` (e.g. `"string"`, `"number"`, `"boolean"`).
+    pub fn param(
+        mut self,
+        name: impl Into<String>,
+        json_type: impl Into<String>,
+        description: impl Into<String>,
+        required: bool,
+    ) -> Self {
+        let name = name.into();
+        self.properties.insert(
+            name.clone(),
+            serde_json::json!({
+                "type": json_type.into(),
+                "description": description.into(),
+            }),
+        );
+        if required {
+            self.required.push(name);
+        }
+        self
+    }
+
+    /// Attach the async handler and finish building the tool.
+    pub fn handler<F, Fut>(self, handler: F) -> Tool
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        let parameters = serde_json::json!({
+            "type": "object",
+            "properties": Value::Object(self.properties),
+            "required": self.required,
+        });
+
+        Tool {
+            name: self.name,
+            description: self.description,
+            parameters,
+            handler: Arc::new(move |input| Box::pin(handler(input))),
+        }
+    }
+}
+
+/// Start building a tool with a name and description.
+pub fn tool(name: impl Into<String>, description: impl Into<String>) -> ToolBuilder {
+    ToolBuilder::new(name, description)
+}
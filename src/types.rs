@@ -0,0 +1,767 @@
+//! # Core Type Definitions
+//!
+//! This module defines the message and content model used throughout the SDK, the
+//! `AgentOptions` configuration type, and the wire-format types used to talk to
+//! OpenAI-compatible chat completion endpoints.
+//!
+//! ## Message Model
+//!
+//! A [`Message`] is a `role` plus an ordered list of [`ContentBlock`]s. A content block
+//! is either plain text, an image, a tool call the model wants to make, or the result of
+//! a tool call. This mirrors the shape of the OpenAI/Anthropic message APIs while staying
+//! provider-agnostic at the public API surface.
+//!
+//! ## Wire Format
+//!
+//! [`OpenAIContent`] and [`OpenAIContentPart`] model the `content` field of an OpenAI
+//! chat message exactly as the API expects it: a plain string for simple text-only
+//! messages (kept for backward compatibility with older single-string servers), or an
+//! array of typed parts once an image enters the conversation.
+
+use crate::context::EvictionPolicy;
+use crate::error::{Error, Result};
+use crate::hooks::Hooks;
+use crate::tools::Tool;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// CONTENT BLOCKS
+// ============================================================================
+
+/// Content block containing plain text generated by the model or provided by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextBlock {
+    /// The text content.
+    pub text: String,
+}
+
+impl TextBlock {
+    /// Create a new text block.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+/// How much visual detail a vision-capable model should spend on an image.
+///
+/// Mirrors the OpenAI `image_url.detail` field: `low` uses a fixed, cheap token budget,
+/// `high` allows the model to examine fine detail at a higher token cost, and `auto`
+/// lets the server decide based on image size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDetail {
+    /// Fixed, low token budget.
+    Low,
+    /// Higher token budget, allows inspecting fine detail.
+    High,
+    /// Let the server decide based on the image.
+    #[default]
+    Auto,
+}
+
+/// Content block representing an image, either a remote URL or an inlined base64 payload.
+///
+/// Images built via [`ImageBlock::from_base64`] are stored as a `data:` URI so that
+/// [`ImageBlock::url`] always returns something that can be dropped straight into an
+/// `image_url.url` field, regardless of how the image was constructed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBlock {
+    url: String,
+    detail: ImageDetail,
+}
+
+impl ImageBlock {
+    /// Build an image block pointing at a remote URL (or an existing `data:` URI).
+    pub fn from_url(url: impl Into<String>) -> Result<Self> {
+        let url = url.into();
+        if url.is_empty() {
+            return Err(Error::invalid_input("image url cannot be empty"));
+        }
+        Ok(Self {
+            url,
+            detail: ImageDetail::default(),
+        })
+    }
+
+    /// Build an image block from base64-encoded bytes, inlining them as a `data:` URI.
+    pub fn from_base64(data: impl AsRef<str>, mime_type: impl AsRef<str>) -> Result<Self> {
+        let data = data.as_ref();
+        let mime_type = mime_type.as_ref();
+        if data.is_empty() {
+            return Err(Error::invalid_input("base64 image data cannot be empty"));
+        }
+        if mime_type.is_empty() {
+            return Err(Error::invalid_input("image mime type cannot be empty"));
+        }
+        Ok(Self {
+            url: format!("data:{mime_type};base64,{data}"),
+            detail: ImageDetail::default(),
+        })
+    }
+
+    /// Set the detail level for this image, returning the modified block.
+    pub fn with_detail(mut self, detail: ImageDetail) -> Self {
+        self.detail = detail;
+        self
+    }
+
+    /// The URL (or `data:` URI) this image resolves to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The requested detail level.
+    pub fn detail(&self) -> ImageDetail {
+        self.detail
+    }
+
+    /// If this image is an inlined `data:` URI (built via [`ImageBlock::from_base64`]),
+    /// returns its `(mime_type, base64_data)`. Returns `None` for a remote URL.
+    pub(crate) fn inline_data(&self) -> Option<(&str, &str)> {
+        self.url.strip_prefix("data:")?.split_once(";base64,")
+    }
+}
+
+/// Content block representing a tool call made by the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolUseBlock {
+    /// Unique identifier for this tool call, echoed back in the matching `ToolResultBlock`.
+    pub id: String,
+    /// Name of the tool being invoked.
+    pub name: String,
+    /// JSON input parameters for the tool.
+    pub input: serde_json::Value,
+}
+
+/// Content block containing the result of a tool execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolResultBlock {
+    /// The `id` of the `ToolUseBlock` this result answers.
+    pub tool_use_id: String,
+    /// The tool's output, rendered as a string.
+    pub content: String,
+    /// Whether the tool execution failed.
+    pub is_error: Option<bool>,
+}
+
+/// Enum representing a unit of content in a message.
+///
+/// Messages can contain multiple content blocks of different types, in any order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentBlock {
+    /// Plain text.
+    Text(TextBlock),
+    /// An image, sent to or received from a vision-capable model.
+    Image(ImageBlock),
+    /// A tool call requested by the model.
+    ToolUse(ToolUseBlock),
+    /// The result of executing a tool call.
+    ToolResult(ToolResultBlock),
+}
+
+// ============================================================================
+// MESSAGES
+// ============================================================================
+
+/// Role of a message participant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    /// System prompt / instructions.
+    System,
+    /// End-user input.
+    User,
+    /// Model output.
+    Assistant,
+    /// Result of a tool execution, sent back to the model.
+    Tool,
+}
+
+/// A single message in a conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Who this message is from.
+    pub role: MessageRole,
+    /// The message's content blocks, in order.
+    pub content: Vec<ContentBlock>,
+}
+
+impl Message {
+    /// Build a message from an explicit role and content blocks.
+    pub fn new(role: MessageRole, content: Vec<ContentBlock>) -> Self {
+        Self { role, content }
+    }
+
+    /// Convenience constructor for a plain-text user message.
+    pub fn user(text: impl Into<String>) -> Self {
+        Self::new(MessageRole::User, vec![ContentBlock::Text(TextBlock::new(text))])
+    }
+
+    /// Convenience constructor for a plain-text system message.
+    pub fn system(text: impl Into<String>) -> Self {
+        Self::new(
+            MessageRole::System,
+            vec![ContentBlock::Text(TextBlock::new(text))],
+        )
+    }
+
+    /// Convenience constructor for an assistant message with arbitrary content blocks.
+    pub fn assistant(content: Vec<ContentBlock>) -> Self {
+        Self::new(MessageRole::Assistant, content)
+    }
+}
+
+// ============================================================================
+// OPENAI WIRE FORMAT
+// ============================================================================
+
+/// The `content` field of an OpenAI chat message.
+///
+/// Serializes as a plain JSON string when text-only (for backward compatibility with
+/// servers that don't accept the array form), or as an array of [`OpenAIContentPart`]
+/// once the message contains an image and [`ContentEncoding::Structured`] is in use
+/// (under [`ContentEncoding::Flattened`] such a message is still a plain string).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAIContent {
+    /// Plain string content.
+    Text(String),
+    /// Array of typed content parts (text and/or images).
+    Parts(Vec<OpenAIContentPart>),
+}
+
+/// A single part of an array-form OpenAI message `content`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenAIContentPart {
+    /// A text segment.
+    Text {
+        /// The text.
+        text: String,
+    },
+    /// An image segment.
+    ImageUrl {
+        /// The image URL (or data URI) and requested detail level.
+        image_url: OpenAIImageUrl,
+    },
+}
+
+impl OpenAIContentPart {
+    /// Build a text part.
+    pub fn text(text: impl Into<String>) -> Self {
+        OpenAIContentPart::Text { text: text.into() }
+    }
+
+    /// Build an image part.
+    pub fn image_url(url: impl Into<String>, detail: ImageDetail) -> Self {
+        OpenAIContentPart::ImageUrl {
+            image_url: OpenAIImageUrl {
+                url: url.into(),
+                detail,
+            },
+        }
+    }
+}
+
+/// The `image_url` object nested inside an [`OpenAIContentPart::ImageUrl`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIImageUrl {
+    /// Remote URL or `data:` URI.
+    pub url: String,
+    /// Requested detail level.
+    pub detail: ImageDetail,
+}
+
+/// How a message's content blocks are encoded into the OpenAI wire `content` field.
+///
+/// Some OpenAI-compatible backends only accept a plain string `content`, with no support
+/// for the structured array form the Vision API uses to carry images. `Flattened` targets
+/// those servers by folding images into the text as markdown image links instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentEncoding {
+    /// Mixed text/image content serializes as [`OpenAIContent::Parts`] (the default).
+    #[default]
+    Structured,
+    /// Mixed text/image content collapses to a single [`OpenAIContent::Text`] string,
+    /// with each image emitted inline as a markdown image link.
+    Flattened,
+}
+
+/// Convert a message's content blocks into the OpenAI wire `content` representation.
+///
+/// Text-only content always collapses to [`OpenAIContent::Text`] (blocks joined by `\n`)
+/// to stay compatible with servers that only accept a plain string. Once an image is
+/// present, `encoding` decides how it's represented: [`ContentEncoding::Structured`]
+/// (the default) emits [`OpenAIContent::Parts`], the only form the Vision API accepts for
+/// mixed content; [`ContentEncoding::Flattened`] instead concatenates everything into a
+/// single string, rendering each image as a markdown image link, for backends that only
+/// accept a plain `content` string.
+pub(crate) fn content_to_openai(content: &[ContentBlock], encoding: ContentEncoding) -> OpenAIContent {
+    let has_image = content.iter().any(|b| matches!(b, ContentBlock::Image(_)));
+
+    if !has_image {
+        let text = content
+            .iter()
+            .filter_map(|b| match b {
+                ContentBlock::Text(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        return OpenAIContent::Text(text);
+    }
+
+    if encoding == ContentEncoding::Flattened {
+        return OpenAIContent::Text(flatten_content_to_markdown(content));
+    }
+
+    let parts = content
+        .iter()
+        .filter_map(|b| match b {
+            ContentBlock::Text(t) => {
+                if t.text.trim().is_empty() {
+                    log::warn!("message contains an empty or whitespace-only text block");
+                }
+                Some(OpenAIContentPart::text(t.text.clone()))
+            }
+            ContentBlock::Image(img) => {
+                let url = img.url();
+                if url.len() > 100 {
+                    let prefix: String = url.chars().take(40).collect();
+                    log::debug!("image url: {}... ({} chars)", prefix, url.len());
+                } else {
+                    log::debug!("image url: {}", url);
+                }
+                log::debug!("- Image: {} (detail: {:?})", url, img.detail());
+                Some(OpenAIContentPart::image_url(url, img.detail()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    OpenAIContent::Parts(parts)
+}
+
+/// Render mixed text/image content as a single markdown string, with each image emitted
+/// as a `![](<url>)` link using the same URL [`ImageBlock::url`] returns (data URI or
+/// remote URL alike).
+fn flatten_content_to_markdown(content: &[ContentBlock]) -> String {
+    let mut out = String::new();
+    for block in content {
+        match block {
+            ContentBlock::Text(t) => {
+                if t.text.trim().is_empty() {
+                    log::warn!("message contains an empty or whitespace-only text block");
+                }
+                out.push_str(&t.text);
+            }
+            ContentBlock::Image(img) => {
+                out.push('\n');
+                out.push_str("![](");
+                out.push_str(img.url());
+                out.push(')');
+                out.push('\n');
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+// ============================================================================
+// AGENT OPTIONS
+// ============================================================================
+
+/// Configuration options for agents, built using the builder pattern.
+///
+/// Contains the system prompt, model, base URL, and execution settings shared by
+/// [`crate::query`] and [`crate::Client`].
+#[derive(Clone)]
+pub struct AgentOptions {
+    pub(crate) system_prompt: Option<String>,
+    pub(crate) model: String,
+    pub(crate) base_url: String,
+    pub(crate) temperature: Option<f32>,
+    pub(crate) max_tokens: Option<u32>,
+    pub(crate) timeout: Option<std::time::Duration>,
+    pub(crate) hooks: Hooks,
+    pub(crate) content_encoding: ContentEncoding,
+    pub(crate) tools: Vec<Tool>,
+    pub(crate) max_steps: usize,
+    pub(crate) max_context_tokens: Option<usize>,
+    pub(crate) eviction_policy: EvictionPolicy,
+    pub(crate) upload_endpoint: Option<String>,
+    pub(crate) auto_upload_threshold: Option<usize>,
+    pub(crate) poll_interval: std::time::Duration,
+    pub(crate) poll_timeout: std::time::Duration,
+}
+
+/// Default cap on the number of tool-call round trips a single [`crate::Client::send`]
+/// call will make before giving up on reaching a final text answer.
+const DEFAULT_MAX_STEPS: usize = 10;
+
+/// Default interval between polls of a job-based backend's status endpoint.
+const DEFAULT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Default total time to wait for a job-based backend to reach a terminal status.
+const DEFAULT_POLL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+impl std::fmt::Debug for AgentOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentOptions")
+            .field("system_prompt", &self.system_prompt)
+            .field("model", &self.model)
+            .field("base_url", &self.base_url)
+            .field("temperature", &self.temperature)
+            .field("max_tokens", &self.max_tokens)
+            .field("timeout", &self.timeout)
+            .field("content_encoding", &self.content_encoding)
+            .field("tools", &self.tools.iter().map(Tool::name).collect::<Vec<_>>())
+            .field("max_steps", &self.max_steps)
+            .field("max_context_tokens", &self.max_context_tokens)
+            .field("eviction_policy", &self.eviction_policy)
+            .field("upload_endpoint", &self.upload_endpoint)
+            .field("auto_upload_threshold", &self.auto_upload_threshold)
+            .field("poll_interval", &self.poll_interval)
+            .field("poll_timeout", &self.poll_timeout)
+            .finish()
+    }
+}
+
+impl AgentOptions {
+    /// Start building a new set of agent options.
+    pub fn builder() -> AgentOptionsBuilder {
+        AgentOptionsBuilder::default()
+    }
+
+    /// The system prompt, if one was configured.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    /// The model name requests are sent with.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// The base URL requests are sent to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The lifecycle hooks registered on this client.
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// How mixed text/image content is encoded into the OpenAI wire `content` field.
+    pub fn content_encoding(&self) -> ContentEncoding {
+        self.content_encoding
+    }
+
+    /// The tools registered on this client, advertised to the model on every request.
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+
+    /// The maximum number of tool-call round trips [`crate::Client::send`] will make
+    /// before giving up and returning an error.
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// The token budget [`crate::Client::send`] trims history down to before each
+    /// request, if one was configured.
+    pub fn max_context_tokens(&self) -> Option<usize> {
+        self.max_context_tokens
+    }
+
+    /// The policy used to decide what to evict when history exceeds
+    /// `max_context_tokens`.
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction_policy
+    }
+
+    /// The media endpoint [`crate::Client::upload_image`] posts `multipart/form-data`
+    /// uploads to, if one was configured.
+    pub fn upload_endpoint(&self) -> Option<&str> {
+        self.upload_endpoint.as_deref()
+    }
+
+    /// The base64 payload size (in characters), above which [`crate::Client::send`]
+    /// automatically uploads an inlined image and replaces it with a hosted URL
+    /// reference, if a threshold was configured.
+    pub fn auto_upload_threshold(&self) -> Option<usize> {
+        self.auto_upload_threshold
+    }
+
+    /// How often to poll a job-based backend's status endpoint while awaiting
+    /// completion. Defaults to 2 seconds.
+    pub fn poll_interval(&self) -> std::time::Duration {
+        self.poll_interval
+    }
+
+    /// Total time to wait for a job-based backend to reach a terminal status before
+    /// giving up with [`crate::Error::timeout`]. Defaults to 120 seconds.
+    pub fn poll_timeout(&self) -> std::time::Duration {
+        self.poll_timeout
+    }
+}
+
+/// Builder for constructing [`AgentOptions`].
+///
+/// Required fields: `model`, `base_url`. Everything else has a sensible default.
+#[derive(Clone, Default)]
+pub struct AgentOptionsBuilder {
+    system_prompt: Option<String>,
+    model: Option<String>,
+    base_url: Option<String>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    timeout: Option<std::time::Duration>,
+    hooks: Hooks,
+    content_encoding: ContentEncoding,
+    tools: Vec<Tool>,
+    max_steps: Option<usize>,
+    max_context_tokens: Option<usize>,
+    eviction_policy: EvictionPolicy,
+    upload_endpoint: Option<String>,
+    auto_upload_threshold: Option<usize>,
+    poll_interval: Option<std::time::Duration>,
+    poll_timeout: Option<std::time::Duration>,
+}
+
+impl std::fmt::Debug for AgentOptionsBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentOptionsBuilder")
+            .field("system_prompt", &self.system_prompt)
+            .field("model", &self.model)
+            .field("base_url", &self.base_url)
+            .field("temperature", &self.temperature)
+            .field("max_tokens", &self.max_tokens)
+            .field("timeout", &self.timeout)
+            .field("content_encoding", &self.content_encoding)
+            .field("tools", &self.tools.iter().map(Tool::name).collect::<Vec<_>>())
+            .field("max_steps", &self.max_steps)
+            .field("max_context_tokens", &self.max_context_tokens)
+            .field("eviction_policy", &self.eviction_policy)
+            .field("upload_endpoint", &self.upload_endpoint)
+            .field("auto_upload_threshold", &self.auto_upload_threshold)
+            .field("poll_interval", &self.poll_interval)
+            .field("poll_timeout", &self.poll_timeout)
+            .finish()
+    }
+}
+
+impl AgentOptionsBuilder {
+    /// Set the system prompt.
+    pub fn system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Set the model name.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Set the base URL of the OpenAI-compatible server.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Set the sampling temperature.
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the maximum number of tokens to generate.
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set the request timeout.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Register lifecycle hooks.
+    pub fn hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Set how mixed text/image content is encoded into the OpenAI wire `content` field.
+    ///
+    /// Defaults to [`ContentEncoding::Structured`] (the array form the Vision API expects)
+    /// for backward compatibility; pass [`ContentEncoding::Flattened`] for servers that
+    /// only accept a plain `content` string.
+    pub fn content_encoding(mut self, encoding: ContentEncoding) -> Self {
+        self.content_encoding = encoding;
+        self
+    }
+
+    /// Register a tool the model may call. Tools are advertised on every request and
+    /// dispatched automatically by [`crate::Client::send`]'s agentic loop.
+    pub fn tool(mut self, tool: Tool) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Set the maximum number of tool-call round trips [`crate::Client::send`] will make
+    /// before giving up and returning an error. Defaults to 10.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Set a token budget that conversation history is trimmed down to before each
+    /// request. Unset by default, meaning history is never trimmed automatically.
+    pub fn max_context_tokens(mut self, max_context_tokens: usize) -> Self {
+        self.max_context_tokens = Some(max_context_tokens);
+        self
+    }
+
+    /// Set the policy used to decide what gets evicted when history exceeds
+    /// `max_context_tokens`. Defaults to [`EvictionPolicy::KeepSystem`].
+    pub fn eviction_policy(mut self, eviction_policy: EvictionPolicy) -> Self {
+        self.eviction_policy = eviction_policy;
+        self
+    }
+
+    /// Set the media endpoint [`crate::Client::upload_image`] posts `multipart/form-data`
+    /// uploads to.
+    pub fn upload_endpoint(mut self, upload_endpoint: impl Into<String>) -> Self {
+        self.upload_endpoint = Some(upload_endpoint.into());
+        self
+    }
+
+    /// Enable automatic upload: before each request, [`crate::Client::send`] replaces any
+    /// inlined base64 `ImageBlock` whose base64 payload is larger than `threshold`
+    /// characters with a hosted URL uploaded to `upload_endpoint`. Requires
+    /// `upload_endpoint` to also be set; has no effect otherwise.
+    pub fn auto_upload_threshold(mut self, threshold: usize) -> Self {
+        self.auto_upload_threshold = Some(threshold);
+        self
+    }
+
+    /// Set how often to poll a job-based backend's status endpoint while awaiting
+    /// completion. Defaults to 2 seconds.
+    pub fn poll_interval(mut self, poll_interval: std::time::Duration) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Set the total time to wait for a job-based backend to reach a terminal status
+    /// before giving up with [`crate::Error::timeout`]. Defaults to 120 seconds.
+    pub fn poll_timeout(mut self, poll_timeout: std::time::Duration) -> Self {
+        self.poll_timeout = Some(poll_timeout);
+        self
+    }
+
+    /// Validate and build the final [`AgentOptions`].
+    pub fn build(self) -> Result<AgentOptions> {
+        let model = self
+            .model
+            .ok_or_else(|| Error::config("model is required"))?;
+        let base_url = self
+            .base_url
+            .ok_or_else(|| Error::config("base_url is required"))?;
+        if model.is_empty() {
+            return Err(Error::config("model cannot be empty"));
+        }
+        if base_url.is_empty() {
+            return Err(Error::config("base_url cannot be empty"));
+        }
+
+        Ok(AgentOptions {
+            system_prompt: self.system_prompt,
+            model,
+            base_url,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            timeout: self.timeout,
+            hooks: self.hooks,
+            content_encoding: self.content_encoding,
+            tools: self.tools,
+            max_steps: self.max_steps.unwrap_or(DEFAULT_MAX_STEPS),
+            max_context_tokens: self.max_context_tokens,
+            eviction_policy: self.eviction_policy,
+            upload_endpoint: self.upload_endpoint,
+            auto_upload_threshold: self.auto_upload_threshold,
+            poll_interval: self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL),
+            poll_timeout: self.poll_timeout.unwrap_or(DEFAULT_POLL_TIMEOUT),
+        })
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_encoding_is_default() {
+        let options = AgentOptions::builder()
+            .model("test-model")
+            .base_url("http://localhost:1234/v1")
+            .build()
+            .unwrap();
+        assert_eq!(options.content_encoding(), ContentEncoding::Structured);
+    }
+
+    #[test]
+    fn test_flattened_encoding_emits_markdown_image_link() {
+        let content = vec![
+            ContentBlock::Text(TextBlock::new("Look at this:")),
+            ContentBlock::Image(ImageBlock::from_url("https://example.com/diagram.png").unwrap()),
+            ContentBlock::Text(TextBlock::new("What do you see?")),
+        ];
+
+        let result = content_to_openai(&content, ContentEncoding::Flattened);
+
+        match result {
+            OpenAIContent::Text(text) => {
+                assert!(text.contains("Look at this:"));
+                assert!(text.contains("![](https://example.com/diagram.png)"));
+                assert!(text.contains("What do you see?"));
+            }
+            OpenAIContent::Parts(_) => panic!("flattened encoding should produce a single string"),
+        }
+    }
+
+    #[test]
+    fn test_flattened_encoding_reuses_data_uri() {
+        let image = ImageBlock::from_base64("AAAA", "image/png").unwrap();
+        let content = vec![ContentBlock::Image(image.clone())];
+
+        let result = content_to_openai(&content, ContentEncoding::Flattened);
+
+        match result {
+            OpenAIContent::Text(text) => {
+                assert_eq!(text, format!("\n![]({})\n", image.url()));
+            }
+            OpenAIContent::Parts(_) => panic!("flattened encoding should produce a single string"),
+        }
+    }
+
+    #[test]
+    fn test_structured_encoding_unchanged_for_mixed_content() {
+        let content = vec![
+            ContentBlock::Text(TextBlock::new("Check this out:")),
+            ContentBlock::Image(ImageBlock::from_url("https://example.com/img.jpg").unwrap()),
+        ];
+
+        let result = content_to_openai(&content, ContentEncoding::Structured);
+
+        assert!(matches!(result, OpenAIContent::Parts(_)));
+    }
+}
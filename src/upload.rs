@@ -0,0 +1,60 @@
+//! # Image Upload
+//!
+//! Uploads raw image bytes to a configurable media endpoint as `multipart/form-data`,
+//! for backends that serve hosted images rather than accepting inlined `data:` URIs.
+//! Used directly by [`crate::Client::upload_image`], and automatically by
+//! [`crate::Client::send`] to replace base64 [`crate::ImageBlock`]s over
+//! `AgentOptions::auto_upload_threshold` with a hosted URL before the request is
+//! serialized, keeping large images out of the request body and debug logs.
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UploadResponse {
+    url: String,
+}
+
+/// Build a single-part `multipart/form-data` body carrying the image bytes under a
+/// `file` field, matching the convention most OpenAI-compatible media endpoints expect.
+pub(crate) fn build_form(bytes: Vec<u8>, mime: &str) -> Result<reqwest::multipart::Form> {
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name("upload")
+        .mime_str(mime)
+        .map_err(|e| Error::invalid_input(format!("invalid image mime type '{mime}': {e}")))?;
+    Ok(reqwest::multipart::Form::new().part("file", part))
+}
+
+/// Extract the hosted URL from an upload endpoint's response.
+pub(crate) fn parse_response(response: UploadResponse) -> String {
+    response.url
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_form_rejects_invalid_mime_type() {
+        let result = build_form(vec![1, 2, 3], "not a mime type");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_form_accepts_valid_mime_type() {
+        let result = build_form(vec![1, 2, 3], "image/png");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_parse_response_extracts_url() {
+        let response = UploadResponse {
+            url: "https://media.example.com/abc.png".to_string(),
+        };
+        assert_eq!(parse_response(response), "https://media.example.com/abc.png");
+    }
+}
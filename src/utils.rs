@@ -0,0 +1,195 @@
+//! # Internal Streaming Utilities
+//!
+//! Low-level helpers for parsing Server-Sent Events (SSE) frames out of a raw byte stream
+//! and for aggregating the incremental `tool_calls` deltas an OpenAI-compatible streaming
+//! response sends one token at a time.
+
+use crate::error::Result;
+use crate::types::ToolUseBlock;
+use serde::Deserialize;
+
+/// Extract the JSON payload from a single SSE `data:` line, if this line carries one.
+///
+/// Returns `None` for blank lines, comments, and the terminal `[DONE]` sentinel.
+pub(crate) fn parse_sse_data(line: &str) -> Option<&str> {
+    let line = line.trim_end_matches('\r');
+    let payload = line.strip_prefix("data:")?.trim_start();
+    if payload.is_empty() || payload == "[DONE]" {
+        return None;
+    }
+    Some(payload)
+}
+
+// ============================================================================
+// TOOL CALL AGGREGATION
+// ============================================================================
+
+/// One `delta.tool_calls[i]` entry from a streaming chat completion chunk.
+///
+/// The model streams a tool call's `id` and function `name` once (on the chunk where the
+/// call starts) and its `arguments` incrementally, one fragment per chunk, all keyed by
+/// `index` so fragments for interleaved parallel tool calls can be told apart.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct StreamToolCallDelta {
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<StreamFunctionDelta>,
+}
+
+/// The `function` object nested inside a [`StreamToolCallDelta`].
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct StreamFunctionDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+/// Accumulates streamed [`StreamToolCallDelta`] fragments into complete [`ToolUseBlock`]s.
+#[derive(Debug, Default)]
+pub(crate) struct ToolCallAggregator {
+    calls: Vec<PartialToolCall>,
+}
+
+impl ToolCallAggregator {
+    /// Merge one chunk's worth of tool-call deltas into the running state.
+    pub(crate) fn ingest(&mut self, deltas: Vec<StreamToolCallDelta>) {
+        for delta in deltas {
+            if self.calls.len() <= delta.index {
+                self.calls.resize(delta.index + 1, PartialToolCall::default());
+            }
+            let entry = &mut self.calls[delta.index];
+            if let Some(id) = delta.id {
+                entry.id = id;
+            }
+            if let Some(function) = delta.function {
+                if let Some(name) = function.name {
+                    entry.name = name;
+                }
+                if let Some(arguments) = function.arguments {
+                    entry.arguments.push_str(&arguments);
+                }
+            }
+        }
+    }
+
+    /// Whether no tool call fragments have been seen yet.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Finish aggregation, parsing each call's accumulated `arguments` string as JSON.
+    pub(crate) fn finish(self) -> Result<Vec<ToolUseBlock>> {
+        self.calls
+            .into_iter()
+            .map(|call| {
+                let input = if call.arguments.trim().is_empty() {
+                    serde_json::Value::Object(serde_json::Map::new())
+                } else {
+                    serde_json::from_str(&call.arguments)?
+                };
+                Ok(ToolUseBlock {
+                    id: call.id,
+                    name: call.name,
+                    input,
+                })
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_data_strips_prefix() {
+        assert_eq!(parse_sse_data("data: {\"a\":1}"), Some("{\"a\":1}"));
+        assert_eq!(parse_sse_data("data: [DONE]"), None);
+        assert_eq!(parse_sse_data(""), None);
+    }
+
+    #[test]
+    fn test_tool_call_aggregator_merges_fragmented_arguments() {
+        let mut aggregator = ToolCallAggregator::default();
+        aggregator.ingest(vec![StreamToolCallDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            function: Some(StreamFunctionDelta {
+                name: Some("get_weather".to_string()),
+                arguments: Some("{\"city\":".to_string()),
+            }),
+        }]);
+        aggregator.ingest(vec![StreamToolCallDelta {
+            index: 0,
+            id: None,
+            function: Some(StreamFunctionDelta {
+                name: None,
+                arguments: Some("\"paris\"}".to_string()),
+            }),
+        }]);
+
+        let calls = aggregator.finish().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].name, "get_weather");
+        assert_eq!(calls[0].input, serde_json::json!({"city": "paris"}));
+    }
+
+    #[test]
+    fn test_tool_call_aggregator_handles_parallel_calls_by_index() {
+        let mut aggregator = ToolCallAggregator::default();
+        aggregator.ingest(vec![
+            StreamToolCallDelta {
+                index: 0,
+                id: Some("call_a".to_string()),
+                function: Some(StreamFunctionDelta {
+                    name: Some("first".to_string()),
+                    arguments: Some("{}".to_string()),
+                }),
+            },
+            StreamToolCallDelta {
+                index: 1,
+                id: Some("call_b".to_string()),
+                function: Some(StreamFunctionDelta {
+                    name: Some("second".to_string()),
+                    arguments: Some("{}".to_string()),
+                }),
+            },
+        ]);
+
+        let calls = aggregator.finish().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "first");
+        assert_eq!(calls[1].name, "second");
+    }
+
+    #[test]
+    fn test_tool_call_aggregator_empty_arguments_become_empty_object() {
+        let mut aggregator = ToolCallAggregator::default();
+        aggregator.ingest(vec![StreamToolCallDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            function: Some(StreamFunctionDelta {
+                name: Some("ping".to_string()),
+                arguments: None,
+            }),
+        }]);
+
+        let calls = aggregator.finish().unwrap();
+        assert_eq!(calls[0].input, serde_json::json!({}));
+    }
+}